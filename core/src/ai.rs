@@ -0,0 +1,193 @@
+//! A built-in AI player, which tries every placement of the active and held pieces and picks the one that leaves
+//! the best board, judged by a weighted heuristic.
+
+use crate::game_state::{GameInput, GameState};
+use crate::grid::{Grid, GRID_COUNT_COLS, GRID_COUNT_ROWS};
+use crate::piece::Piece;
+use crate::placement::{find_placements, plan_inputs, PiecePosition};
+use std::collections::VecDeque;
+
+/// How much each feature of a board counts towards its score. Lines cleared count for a placement, while the
+/// rest count against it.
+#[derive(Clone, Copy, Debug)]
+pub struct HeuristicWeights {
+    pub lines_cleared: f64,
+    pub holes: f64,
+    pub bumpiness: f64,
+    pub aggregate_height: f64,
+    pub well_depth: f64,
+}
+
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        Self {
+            lines_cleared: 0.76,
+            holes: 0.36,
+            bumpiness: 0.18,
+            aggregate_height: 0.51,
+            well_depth: 0.1,
+        }
+    }
+}
+
+/// The features of a board that the heuristic scores.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoardFeatures {
+    /// Empty cells with a block somewhere above them
+    pub holes: usize,
+    /// The total difference in height between neighbouring columns
+    pub bumpiness: usize,
+    pub aggregate_height: usize,
+    /// The total depth of every column that's lower than the columns (or walls) on both sides of it
+    pub well_depth: usize,
+}
+
+impl BoardFeatures {
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut features = Self::default();
+        let mut heights = [0; GRID_COUNT_COLS];
+
+        for (col, height) in heights.iter_mut().enumerate() {
+            let top_row = (0..GRID_COUNT_ROWS).find(|&row| grid.has_block_at_cell(row, col));
+
+            if let Some(top_row) = top_row {
+                *height = GRID_COUNT_ROWS - top_row;
+                features.holes += (top_row..GRID_COUNT_ROWS)
+                    .filter(|&row| !grid.has_block_at_cell(row, col))
+                    .count();
+            }
+        }
+
+        for (col, &height) in heights.iter().enumerate() {
+            features.aggregate_height += height;
+
+            if let Some(&right_height) = heights.get(col + 1) {
+                features.bumpiness += height.abs_diff(right_height);
+            }
+
+            // The walls are as high as they need to be, so the edge columns only depend on their one neighbour.
+            let left_height = col.checked_sub(1).map_or(usize::MAX, |left| heights[left]);
+            let right_height = heights.get(col + 1).copied().unwrap_or(usize::MAX);
+            let edge_height = left_height.min(right_height);
+
+            if edge_height != usize::MAX && edge_height > height {
+                features.well_depth += edge_height - height;
+            }
+        }
+
+        features
+    }
+}
+
+impl HeuristicWeights {
+    /// Score a board, where higher is better.
+    pub fn score(&self, grid: &Grid, lines_cleared: usize) -> f64 {
+        let features = BoardFeatures::from_grid(grid);
+
+        self.lines_cleared * lines_cleared as f64
+            - self.holes * features.holes as f64
+            - self.bumpiness * features.bumpiness as f64
+            - self.aggregate_height * features.aggregate_height as f64
+            - self.well_depth * features.well_depth as f64
+    }
+}
+
+/// A final resting place for a piece, along with the score of the board it leaves behind.
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    pub piece: Piece,
+    pub position: PiecePosition,
+    /// Whether the piece has to be swapped in from hold (or the previews) first.
+    pub is_hold: bool,
+    pub score: f64,
+}
+
+/// Find every placement of the active piece, and of the piece hold would swap in, best first.
+pub fn rank_placements(game_state: &GameState, weights: &HeuristicWeights) -> Vec<Placement> {
+    let mut placements = Vec::new();
+    let mut starts = vec![(
+        game_state.get_active_piece(),
+        game_state.get_active_piece_position(),
+        false,
+    )];
+
+    if game_state.get_is_hold_available() {
+        // Try the hold out on a copy of the game, to find out which piece it swaps in and where that spawns.
+        let mut held_game_state = game_state.clone_without_replay();
+
+        held_game_state.step(GameInput {
+            hold_piece: true,
+            ..Default::default()
+        });
+
+        if held_game_state.get_is_piece_active() {
+            starts.push((
+                held_game_state.get_active_piece(),
+                held_game_state.get_active_piece_position(),
+                true,
+            ));
+        }
+    }
+
+    let grid = game_state.get_grid_locked();
+
+    for (piece, from, is_hold) in starts {
+        for position in find_placements(grid, &piece, from) {
+            let mut next_grid = *grid;
+            let lines_cleared = position.place(&mut next_grid, &piece);
+
+            placements.push(Placement {
+                piece,
+                position,
+                is_hold,
+                score: weights.score(&next_grid, lines_cleared),
+            });
+        }
+    }
+
+    placements.sort_by(|a, b| b.score.total_cmp(&a.score));
+    placements
+}
+
+/// Plays a game by making the best placement it can find for each piece.
+#[derive(Clone, Debug, Default)]
+pub struct AiPlayer {
+    pub weights: HeuristicWeights,
+    // Inputs for the placement being made, to be fed to the game one tick at a time
+    inputs: VecDeque<GameInput>,
+}
+
+impl AiPlayer {
+    pub fn new(weights: HeuristicWeights) -> Self {
+        Self {
+            weights,
+            inputs: VecDeque::new(),
+        }
+    }
+
+    /// Get the input for the next tick of the given game.
+    pub fn get_input(&mut self, game_state: &GameState) -> GameInput {
+        if let Some(input) = self.inputs.pop_front() {
+            return input;
+        }
+
+        if game_state.get_is_game_over() || !game_state.get_is_piece_active() {
+            return GameInput::default();
+        }
+
+        // The best placements aren't always reachable in time (e.g. at high gravity), so fall back to the next
+        // best, and just drop the piece if nothing works.
+        let inputs = rank_placements(game_state, &self.weights)
+            .iter()
+            .find_map(|placement| plan_inputs(game_state, placement.position, placement.is_hold))
+            .unwrap_or_else(|| {
+                vec![GameInput {
+                    hard_drop: true,
+                    ..Default::default()
+                }]
+            });
+
+        self.inputs.extend(inputs);
+        self.inputs.pop_front().unwrap_or_default()
+    }
+}
@@ -0,0 +1,120 @@
+use crate::scoring::ClearKind;
+use crate::storage::read_records;
+
+const ATTACK_TABLE_PATH: &str = "./.attack";
+const COMBO_BONUS_COUNT: usize = 12;
+
+/// How many rows of garbage each kind of clear sends to the opponent in versus play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttackTable {
+    pub single: usize,
+    pub double: usize,
+    pub triple: usize,
+    pub tetris: usize,
+    pub t_spin_mini_single: usize,
+    pub t_spin_mini_double: usize,
+    pub t_spin_single: usize,
+    pub t_spin_double: usize,
+    pub t_spin_triple: usize,
+    /// Extra rows for a difficult clear that continues a back-to-back chain.
+    pub back_to_back_bonus: usize,
+    /// Extra rows for clearing every block from the playfield.
+    pub perfect_clear: usize,
+    /// Extra rows for each combo count, starting from the first clear of a combo. The last bonus applies to any
+    /// longer combo.
+    pub combo_bonuses: [usize; COMBO_BONUS_COUNT],
+}
+
+impl Default for AttackTable {
+    fn default() -> Self {
+        Self {
+            single: 0,
+            double: 1,
+            triple: 2,
+            tetris: 4,
+            t_spin_mini_single: 0,
+            t_spin_mini_double: 1,
+            t_spin_single: 2,
+            t_spin_double: 4,
+            t_spin_triple: 6,
+            back_to_back_bonus: 1,
+            perfect_clear: 10,
+            combo_bonuses: [0, 0, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5],
+        }
+    }
+}
+
+impl AttackTable {
+    /// Load the attack table from `./.attack`, falling back to the defaults for anything that isn't in it. Each
+    /// line is "key=rows", where the keys are the field names, and combo bonuses are "combo_0" to "combo_11".
+    pub fn load() -> Self {
+        let mut attack_table = Self::default();
+
+        for (key, value) in read_records(ATTACK_TABLE_PATH) {
+            if let Some(rows) = attack_table.get_rows_mut(&key) {
+                *rows = value as usize;
+            }
+        }
+
+        attack_table
+    }
+
+    fn get_rows_mut(&mut self, key: &str) -> Option<&mut usize> {
+        if let Some(combo) = key.strip_prefix("combo_") {
+            return self.combo_bonuses.get_mut(combo.parse::<usize>().ok()?);
+        }
+
+        match key {
+            "single" => Some(&mut self.single),
+            "double" => Some(&mut self.double),
+            "triple" => Some(&mut self.triple),
+            "tetris" => Some(&mut self.tetris),
+            "t_spin_mini_single" => Some(&mut self.t_spin_mini_single),
+            "t_spin_mini_double" => Some(&mut self.t_spin_mini_double),
+            "t_spin_single" => Some(&mut self.t_spin_single),
+            "t_spin_double" => Some(&mut self.t_spin_double),
+            "t_spin_triple" => Some(&mut self.t_spin_triple),
+            "back_to_back_bonus" => Some(&mut self.back_to_back_bonus),
+            "perfect_clear" => Some(&mut self.perfect_clear),
+            _ => None,
+        }
+    }
+
+    /// Get the number of garbage rows sent by a lock. Locks that don't clear any rows never send anything.
+    pub fn get_attack(
+        &self,
+        clear_kind: ClearKind,
+        is_back_to_back: bool,
+        combo: usize,
+        is_perfect_clear: bool,
+    ) -> usize {
+        let base = match clear_kind {
+            ClearKind::Single => self.single,
+            ClearKind::Double => self.double,
+            ClearKind::Triple => self.triple,
+            ClearKind::Tetris => self.tetris,
+            ClearKind::TSpinMini(0) | ClearKind::TSpin(0) => return 0,
+            ClearKind::TSpinMini(1) => self.t_spin_mini_single,
+            ClearKind::TSpinMini(_) => self.t_spin_mini_double,
+            ClearKind::TSpin(1) => self.t_spin_single,
+            ClearKind::TSpin(2) => self.t_spin_double,
+            ClearKind::TSpin(_) => self.t_spin_triple,
+        };
+
+        let back_to_back_bonus = if is_back_to_back {
+            self.back_to_back_bonus
+        } else {
+            0
+        };
+
+        let combo_bonus = self.combo_bonuses[combo.min(COMBO_BONUS_COUNT - 1)];
+
+        let perfect_clear_bonus = if is_perfect_clear {
+            self.perfect_clear
+        } else {
+            0
+        };
+
+        base + back_to_back_bonus + combo_bonus + perfect_clear_bonus
+    }
+}
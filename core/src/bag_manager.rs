@@ -1,55 +1,63 @@
 use std::fmt::Display;
 
 use crate::piece::{pieces, Piece};
+use crate::randomizer::{assert_peek_offset, Randomizer};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-fn get_random_bag() -> [Piece; 7] {
-    let mut rng = thread_rng();
-
-    let mut bag = [
-        pieces::I,
-        pieces::J,
-        pieces::L,
-        pieces::O,
-        pieces::S,
-        pieces::T,
-        pieces::Z,
-    ];
+/// Get a random seed for a new game. These are kept to 32 bits so they're short enough to show and type in.
+pub fn get_random_seed() -> u64 {
+    thread_rng().gen::<u32>() as u64
+}
 
-    bag.shuffle(&mut rng);
+fn get_random_bag(rng: &mut ChaCha8Rng) -> [Piece; 7] {
+    let mut bag = pieces::ALL;
+    bag.shuffle(rng);
     bag
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct BagManager {
     index: isize,
     current_bag: [Piece; 7],
     next_bag: [Piece; 7],
+    // ChaCha8 is used rather than StdRng because its output is guaranteed to be the same across platforms
+    // and versions, so a seed always produces the same sequence of pieces.
+    rng: ChaCha8Rng,
 }
 
 impl BagManager {
-    pub fn new() -> Self {
+    pub fn with_seed(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let current_bag = get_random_bag(&mut rng);
+        let next_bag = get_random_bag(&mut rng);
+
         Self {
             index: -1,
-            current_bag: get_random_bag(),
-            next_bag: get_random_bag(),
+            current_bag,
+            next_bag,
+            rng,
         }
     }
+}
 
-    pub fn next(&mut self) -> Piece {
+/// The standard 7-bag randomizer. Every piece is dealt once, in a random order, before any are repeated.
+impl Randomizer for BagManager {
+    fn next(&mut self) -> Piece {
         self.index += 1;
 
         if self.index > 6 {
             self.current_bag = self.next_bag;
-            self.next_bag = get_random_bag();
+            self.next_bag = get_random_bag(&mut self.rng);
             self.index = 0;
         }
 
         self.current_bag[self.index as usize]
     }
 
-    pub fn peek(&self, offset: usize) -> Piece {
+    fn peek(&self, offset: usize) -> Piece {
+        assert_peek_offset(offset);
         let next_index = (self.index + offset as isize) as usize;
 
         if next_index > 6 {
@@ -58,6 +66,10 @@ impl BagManager {
             self.current_bag[next_index]
         }
     }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
 }
 
 impl Display for BagManager {
@@ -0,0 +1,378 @@
+//! Runs games headlessly, from a saved replay, an input script, or a bot speaking the Tetris Bot Protocol, and
+//! prints the final board and stats. It can also benchmark the built-in AI, by reporting the average lines it
+//! clears per game over a set of seeds.
+//!
+//! Input scripts have one step per line, in the form `<ticks> <inputs>`, where `inputs` is a string of input
+//! codes that are all applied on every one of those ticks, or `-` for no input. Blank lines and anything after
+//! a `#` are ignored. The input codes are:
+//!
+//! - `L` / `R`: shift left / right
+//! - `D`: soft drop
+//! - `X` / `Z` / `A`: rotate right / left / 180
+//! - `H`: hard drop
+//! - `C`: hold
+
+use bloxide_core::ai::AiPlayer;
+use bloxide_core::bag_manager::get_random_seed;
+use bloxide_core::bot::{Bot, BotPlayer};
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+use bloxide_core::replay::{Replay, ReplayPlayer};
+use std::path::Path;
+use std::time::Duration;
+use std::{env, fs, process, thread};
+
+const USAGE: &str = "Usage:
+  bloxide-sim replay <path>
+  bloxide-sim script <path> [--mode <mode>] [--randomizer <randomizer>] [--seed <seed>] [--ticks <ticks>]
+  bloxide-sim bot <command> [--mode <mode>] [--randomizer <randomizer>] [--seed <seed>] [--ticks <ticks>]
+  bloxide-sim benchmark [--mode <mode>] [--randomizer <randomizer>] [--seed <first seed>] [--games <games>] [--ticks <ticks>]";
+
+// Two minutes of play, when a bot game isn't given a length
+const DEFAULT_BOT_TICKS: usize = 60 * 60 * 2;
+
+// Benchmarks play seeds counting up from the first one, with each game cut off after ten minutes.
+const DEFAULT_BENCHMARK_GAMES: usize = 10;
+const DEFAULT_BENCHMARK_TICKS: usize = 60 * 60 * 10;
+
+fn parse_input(codes: &str) -> Result<GameInput, String> {
+    let mut input = GameInput::default();
+
+    if codes == "-" {
+        return Ok(input);
+    }
+
+    for code in codes.chars() {
+        match code.to_ascii_uppercase() {
+            'L' => input.shift_left = true,
+            'R' => input.shift_right = true,
+            'D' => input.soft_drop = true,
+            'X' => input.rotate_right = true,
+            'Z' => input.rotate_left = true,
+            'A' => input.rotate_180 = true,
+            'H' => input.hard_drop = true,
+            'C' => input.hold_piece = true,
+            _ => return Err(format!("Unknown input code '{}'", code)),
+        }
+    }
+
+    Ok(input)
+}
+
+/// Parse an input script into the input for every tick.
+fn parse_script(script: &str) -> Result<Vec<GameInput>, String> {
+    let mut inputs = Vec::new();
+
+    for (index, line) in script.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+
+        let [ticks, codes] = parts[..] else {
+            return Err(format!("Line {}: expected <ticks> <inputs>", index + 1));
+        };
+
+        let ticks = ticks
+            .parse::<usize>()
+            .map_err(|_| format!("Line {}: invalid tick count '{}'", index + 1, ticks))?;
+        let input = parse_input(codes).map_err(|error| format!("Line {}: {}", index + 1, error))?;
+
+        inputs.extend(std::iter::repeat_n(input, ticks));
+    }
+
+    Ok(inputs)
+}
+
+fn print_summary(game_state: &GameState) {
+    print!("{}", game_state.get_grid_locked());
+    println!();
+    println!("Mode:           {}", game_state.get_mode().get_label());
+    println!(
+        "Randomizer:     {}",
+        game_state.get_randomizer_kind().get_label()
+    );
+    println!("Seed:           {}", game_state.get_seed());
+    println!("Ticks:          {}", game_state.get_tick());
+    println!("Score:          {}", game_state.get_score());
+    println!("Lines:          {}", game_state.get_rows_cleared());
+    println!(
+        "Level:          {}",
+        game_state
+            .get_master_level()
+            .unwrap_or(game_state.get_level())
+    );
+    println!("Perfect clears: {}", game_state.get_perfect_clears());
+
+    let stats = game_state.get_stats();
+    let elapsed_ms = game_state.get_elapsed_ms();
+
+    println!(
+        "Pieces:         {} ({:.2} PPS)",
+        stats.pieces_placed,
+        stats.get_pps(elapsed_ms)
+    );
+    println!(
+        "Keys:           {} ({:.2} KPP)",
+        stats.keys_pressed,
+        stats.get_kpp()
+    );
+    println!(
+        "Clears:         {} singles, {} doubles, {} triples, {} tetrises, {} T-spins",
+        stats.singles, stats.doubles, stats.triples, stats.tetrises, stats.t_spins
+    );
+    println!("Max combo:      {}", stats.max_combo);
+    println!(
+        "Attack:         {} ({:.1} APM)",
+        stats.attack_sent,
+        stats.get_apm(elapsed_ms)
+    );
+
+    if let Some(finesse_percentage) = game_state.get_finesse_percentage() {
+        println!(
+            "Finesse:        {:.1}% ({} faults in {} pieces)",
+            finesse_percentage,
+            game_state.get_finesse_faults(),
+            game_state.get_finesse_pieces()
+        );
+    }
+
+    if let Some(cheese_remaining) = game_state.get_cheese_remaining() {
+        println!("Cheese left:    {}", cheese_remaining);
+    }
+
+    println!(
+        "Result:         {}",
+        if game_state.get_is_goal_reached() {
+            "finished"
+        } else if game_state.get_is_game_over() {
+            "topped out"
+        } else {
+            "unfinished"
+        }
+    );
+}
+
+fn run_replay(path: &Path) -> Result<(), String> {
+    let replay = Replay::load(path).map_err(|error| error.to_string())?;
+    let mut replay_player = ReplayPlayer::new(replay);
+
+    while !replay_player.get_is_finished() {
+        replay_player.step();
+    }
+
+    print_summary(&replay_player.game_state);
+
+    if replay_player.get_is_rules_mismatch() {
+        println!("Warning:        recorded under different rules");
+    }
+
+    match replay_player.get_divergence_tick() {
+        Some(tick) => println!("Diverged:       at tick {}", tick),
+        None => println!("Diverged:       no"),
+    }
+
+    Ok(())
+}
+
+/// The options shared by the subcommands that start a new game.
+struct GameOptions {
+    mode: GameMode,
+    randomizer_kind: RandomizerKind,
+    seed: Option<u64>,
+    max_ticks: Option<usize>,
+    game_count: Option<usize>,
+}
+
+fn parse_options(options: &[String]) -> Result<GameOptions, String> {
+    let mut game_options = GameOptions {
+        mode: GameMode::Marathon,
+        randomizer_kind: RandomizerKind::SevenBag,
+        seed: None,
+        max_ticks: None,
+        game_count: None,
+    };
+
+    for pair in options.chunks(2) {
+        let [option, value] = pair else {
+            return Err(format!("Missing value for {}", pair[0]));
+        };
+
+        match option.as_str() {
+            "--mode" => {
+                game_options.mode =
+                    GameMode::from_id(value).ok_or(format!("Unknown mode '{}'", value))?
+            }
+            "--randomizer" => {
+                game_options.randomizer_kind = RandomizerKind::from_id(value)
+                    .ok_or(format!("Unknown randomizer '{}'", value))?
+            }
+            "--seed" => {
+                game_options.seed = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid seed '{}'", value))?,
+                )
+            }
+            "--ticks" => {
+                game_options.max_ticks = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid tick count '{}'", value))?,
+                )
+            }
+            "--games" => {
+                game_options.game_count = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid game count '{}'", value))?,
+                )
+            }
+            _ => return Err(format!("Unknown option '{}'", option)),
+        }
+    }
+
+    Ok(game_options)
+}
+
+fn run_script(path: &Path, options: &[String]) -> Result<(), String> {
+    let game_options = parse_options(options)?;
+    let script = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let inputs = parse_script(&script)?;
+
+    // Scripts always use the default handling, so they play out the same no matter who runs them.
+    let mut game_state = GameState::new(
+        game_options.mode,
+        game_options.randomizer_kind,
+        game_options.seed.unwrap_or_else(get_random_seed),
+        HandlingConfig::default(),
+    );
+
+    for input in inputs {
+        if game_state.get_is_game_over() || game_options.max_ticks == Some(game_state.get_tick()) {
+            break;
+        }
+
+        game_state.step(input);
+    }
+
+    print_summary(&game_state);
+
+    Ok(())
+}
+
+fn run_bot(command: &str, options: &[String]) -> Result<(), String> {
+    let game_options = parse_options(options)?;
+    let max_ticks = game_options.max_ticks.unwrap_or(DEFAULT_BOT_TICKS);
+
+    let bot = Bot::spawn(command, &[]).map_err(|error| error.to_string())?;
+    let mut bot_player = BotPlayer::new(bot);
+
+    let mut game_state = GameState::new(
+        game_options.mode,
+        game_options.randomizer_kind,
+        game_options.seed.unwrap_or_else(get_random_seed),
+        HandlingConfig::default(),
+    );
+
+    // Bots take as long as they need to think, so the game only moves on once they've decided.
+    while !game_state.get_is_game_over() && game_state.get_tick() < max_ticks {
+        let input = bot_player.get_input(&game_state);
+
+        if let Some(error) = bot_player.get_error() {
+            return Err(error.to_string());
+        }
+
+        if bot_player.get_is_thinking() {
+            thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+
+        game_state.step(input);
+    }
+
+    print_summary(&game_state);
+    println!("Bot:            {}", bot_player.get_bot().get_name());
+
+    Ok(())
+}
+
+/// Play a game with the built-in AI, from the given seed.
+fn play_ai_game(game_options: &GameOptions, seed: u64, max_ticks: usize) -> GameState {
+    let mut ai_player = AiPlayer::default();
+    let mut game_state = GameState::new(
+        game_options.mode,
+        game_options.randomizer_kind,
+        seed,
+        HandlingConfig::default(),
+    );
+
+    while !game_state.get_is_game_over() && game_state.get_tick() < max_ticks {
+        game_state.step(ai_player.get_input(&game_state));
+    }
+
+    game_state
+}
+
+fn run_benchmark(options: &[String]) -> Result<(), String> {
+    let game_options = parse_options(options)?;
+    let first_seed = game_options.seed.unwrap_or(0);
+    let game_count = game_options.game_count.unwrap_or(DEFAULT_BENCHMARK_GAMES);
+    let max_ticks = game_options.max_ticks.unwrap_or(DEFAULT_BENCHMARK_TICKS);
+    let mut total_lines = 0;
+
+    println!("Mode:           {}", game_options.mode.get_label());
+    println!(
+        "Randomizer:     {}",
+        game_options.randomizer_kind.get_label()
+    );
+    println!();
+
+    for seed in (first_seed..).take(game_count) {
+        let game_state = play_ai_game(&game_options, seed, max_ticks);
+        total_lines += game_state.get_rows_cleared();
+
+        println!(
+            "Seed {:<10} {:>6} lines  {}",
+            seed,
+            game_state.get_rows_cleared(),
+            if game_state.get_is_game_over() && !game_state.get_is_goal_reached() {
+                "topped out"
+            } else {
+                "survived"
+            }
+        );
+    }
+
+    println!();
+    println!(
+        "Average lines:  {:.1}",
+        total_lines as f64 / game_count.max(1) as f64
+    );
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.as_slice() {
+        [command, path] if command == "replay" => run_replay(Path::new(path)),
+        [command, path, options @ ..] if command == "script" => {
+            run_script(Path::new(path), options)
+        }
+        [command, bot_command, options @ ..] if command == "bot" => run_bot(bot_command, options),
+        [command, options @ ..] if command == "benchmark" => run_benchmark(options),
+        _ => Err(String::from(USAGE)),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
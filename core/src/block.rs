@@ -0,0 +1,28 @@
+/// An opaque 8-bit RGB color. The simulation doesn't draw anything, so it's up to the renderer to convert this
+/// into whatever color type it uses.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl BlockColor {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        BlockColor { r, g, b }
+    }
+}
+
+/// The color of garbage rows, which aren't made from any one kind of piece.
+pub const GARBAGE_COLOR: BlockColor = BlockColor::new(128, 128, 128);
+
+#[derive(Copy, Clone, Debug)]
+pub struct Block {
+    pub color: BlockColor,
+}
+
+impl Block {
+    pub fn new(color: BlockColor) -> Self {
+        Block { color }
+    }
+}
@@ -0,0 +1,610 @@
+//! Lets external AI engines play the game, using the Tetris Bot Protocol (TBP). Bots run as a separate
+//! process, and exchange JSON messages with the game over stdin and stdout, one per line.
+
+use crate::block::{Block, GARBAGE_COLOR};
+use crate::game_state::{GameInput, GameState};
+use crate::grid::{Grid, GRID_COUNT_COLS, GRID_COUNT_ROWS};
+use crate::piece::{pieces, Piece};
+use crate::placement::{plan_inputs, PiecePosition};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// TBP boards are always 40 rows tall, counted up from the bottom.
+pub const TBP_BOARD_ROWS: usize = 40;
+
+const BOT_START_TIMEOUT: Duration = Duration::from_secs(10);
+// How long a bot gets to exit by itself after being told to quit, before it's killed.
+const BOT_QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Orientation {
+    /// Orientations in the same order as the orientations of a Piece.
+    pub const ALL: [Orientation; 4] = [
+        Orientation::North,
+        Orientation::East,
+        Orientation::South,
+        Orientation::West,
+    ];
+
+    pub fn get_index(&self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|orientation| orientation == self)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+/// Where a piece is placed, in TBP coordinates: x and y are the position of the piece's center of rotation,
+/// counted from the bottom-left of the board.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PieceLocation {
+    #[serde(rename = "type")]
+    pub piece: String,
+    pub orientation: Orientation,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TbpMove {
+    pub location: PieceLocation,
+    pub spin: Spin,
+}
+
+/// A message sent from the game to the bot.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FrontendMessage {
+    Rules {},
+    /// Start thinking about the given position. The queue starts with the active piece, and the board is
+    /// TBP_BOARD_ROWS rows of cells from the bottom up, each either empty, a piece name, or "G" for garbage.
+    Start {
+        hold: Option<String>,
+        queue: Vec<String>,
+        combo: u32,
+        back_to_back: bool,
+        board: Vec<Vec<Option<String>>>,
+    },
+    Stop,
+    Suggest,
+    Play {
+        #[serde(rename = "move")]
+        tbp_move: TbpMove,
+    },
+    NewPiece {
+        piece: String,
+    },
+    Quit,
+}
+
+/// A message sent from the bot to the game.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotMessage {
+    Info {
+        name: String,
+        version: String,
+        author: String,
+        features: Vec<String>,
+    },
+    Ready,
+    Error {
+        reason: String,
+    },
+    /// Moves the bot would make, best first.
+    Suggestion {
+        moves: Vec<TbpMove>,
+    },
+}
+
+#[derive(Debug)]
+pub enum BotError {
+    Io(std::io::Error),
+    Invalid(String),
+    /// The bot didn't accept the game's rules.
+    Rejected(String),
+    Timeout,
+    Exited,
+}
+
+impl Display for BotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BotError::Io(error) => write!(f, "{}", error),
+            BotError::Invalid(reason) => write!(f, "Invalid message from bot: {}", reason),
+            BotError::Rejected(reason) => write!(f, "Bot rejected the rules: {}", reason),
+            BotError::Timeout => write!(f, "Bot took too long to respond"),
+            BotError::Exited => write!(f, "Bot exited"),
+        }
+    }
+}
+
+impl From<std::io::Error> for BotError {
+    fn from(error: std::io::Error) -> Self {
+        BotError::Io(error)
+    }
+}
+
+pub fn get_piece(name: &str) -> Option<Piece> {
+    pieces::ALL.into_iter().find(|piece| piece.name == name)
+}
+
+/// Get the cells a piece covers in the given orientation, relative to its TBP position, with y going up.
+fn get_tbp_offsets(piece: &Piece, orientation: usize) -> [(i32, i32); 4] {
+    let north = match piece.name {
+        "I" => [(-1, 0), (0, 0), (1, 0), (2, 0)],
+        "O" => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        "J" => [(-1, 1), (-1, 0), (0, 0), (1, 0)],
+        "L" => [(1, 1), (-1, 0), (0, 0), (1, 0)],
+        "S" => [(-1, 0), (0, 0), (0, 1), (1, 1)],
+        "Z" => [(-1, 1), (0, 1), (0, 0), (1, 0)],
+        _ => [(-1, 0), (0, 0), (1, 0), (0, 1)],
+    };
+
+    // Each clockwise quarter turn takes (x, y) to (y, -x).
+    north.map(|offset| (0..orientation % 4).fold(offset, |(x, y), _| (y, -x)))
+}
+
+/// Convert a grid cell to TBP coordinates, as (x, y).
+fn to_tbp_cell((row, col): (isize, isize)) -> (i32, i32) {
+    (col as i32, (GRID_COUNT_ROWS as isize - 1 - row) as i32)
+}
+
+pub fn to_piece_location(piece: &Piece, position: PiecePosition) -> PieceLocation {
+    let cells = position.get_cells(piece).into_iter().map(to_tbp_cell);
+    let offsets = get_tbp_offsets(piece, position.orientation);
+
+    // The offsets are the same shape as the cells, so the bottom-left corners of each line up.
+    let (min_x, min_y) = cells.fold((i32::MAX, i32::MAX), |(min_x, min_y), (x, y)| {
+        (min_x.min(x), min_y.min(y))
+    });
+    let min_offset_x = offsets.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_offset_y = offsets.iter().map(|(_, y)| *y).min().unwrap_or(0);
+
+    PieceLocation {
+        piece: String::from(piece.name),
+        orientation: Orientation::ALL[position.orientation % 4],
+        x: min_x - min_offset_x,
+        y: min_y - min_offset_y,
+    }
+}
+
+/// Convert a TBP piece location to a piece and its position on the grid. None if the piece is unknown.
+pub fn from_piece_location(location: &PieceLocation) -> Option<(Piece, PiecePosition)> {
+    let piece = get_piece(&location.piece)?;
+    let orientation = location.orientation.get_index();
+
+    let mut cells: Vec<(isize, isize)> = get_tbp_offsets(&piece, orientation)
+        .iter()
+        .map(|(x, y)| {
+            let row = GRID_COUNT_ROWS as isize - 1 - (location.y + y) as isize;
+            (row, (location.x + x) as isize)
+        })
+        .collect();
+
+    // Line the piece's canvas up with the cells, using the top-left of each.
+    let canvas_cells = PiecePosition {
+        row: 0,
+        col: 0,
+        orientation,
+    }
+    .get_cells(&piece);
+    let canvas_min_row = canvas_cells.iter().map(|(row, _)| *row).min()?;
+    let canvas_min_col = canvas_cells.iter().map(|(_, col)| *col).min()?;
+
+    let position = PiecePosition {
+        row: cells.iter().map(|(row, _)| *row).min()? - canvas_min_row,
+        col: cells.iter().map(|(_, col)| *col).min()? - canvas_min_col,
+        orientation,
+    };
+
+    let mut position_cells = position.get_cells(&piece);
+    position_cells.sort();
+    cells.sort();
+
+    (position_cells == cells).then_some((piece, position))
+}
+
+fn get_cell_name(block: Block) -> String {
+    let name = pieces::ALL
+        .iter()
+        .find(|piece| piece.color == block.color)
+        .map_or("G", |piece| piece.name);
+
+    String::from(name)
+}
+
+pub fn to_board(grid: &Grid) -> Vec<Vec<Option<String>>> {
+    (0..TBP_BOARD_ROWS)
+        .map(|y| {
+            (0..GRID_COUNT_COLS)
+                .map(|col| {
+                    let row = GRID_COUNT_ROWS.checked_sub(y + 1)?;
+                    grid.get_cell(row, col).map(get_cell_name)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Convert a TBP board to a grid. Only whether each cell is filled is kept, so every block is garbage colored.
+/// Rows above the top of the grid are ignored.
+pub fn from_board(board: &[Vec<Option<String>>]) -> Grid {
+    let mut grid = Grid::new();
+
+    for (y, cells) in board.iter().enumerate().take(GRID_COUNT_ROWS) {
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.is_some() {
+                grid.set_cell(
+                    GRID_COUNT_ROWS - 1 - y,
+                    col,
+                    Some(Block::new(GARBAGE_COLOR)),
+                );
+            }
+        }
+    }
+
+    grid
+}
+
+/// Whether two grids have blocks in the same cells, regardless of color.
+fn is_same_shape(a: &Grid, b: &Grid) -> bool {
+    (0..GRID_COUNT_ROWS).all(|row| {
+        (0..GRID_COUNT_COLS)
+            .all(|col| a.has_block_at_cell(row, col) == b.has_block_at_cell(row, col))
+    })
+}
+
+/// A running bot process.
+pub struct Bot {
+    // Only taken when the bot is dropped, to wait for it to exit
+    child: Option<Child>,
+    stdin: ChildStdin,
+    // Messages read from the bot's stdout by a background thread, so reading them never blocks the game
+    messages: Receiver<Result<BotMessage, String>>,
+    name: String,
+}
+
+impl Bot {
+    /// Start a bot, and wait for it to introduce itself and accept the rules.
+    pub fn spawn(command: &str, args: &[String]) -> Result<Self, BotError> {
+        let mut child = Command::new(command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(BotError::Exited);
+        };
+
+        let (sender, messages) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let message = serde_json::from_str(&line).map_err(|error| error.to_string());
+
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut bot = Self {
+            child: Some(child),
+            stdin,
+            messages,
+            name: String::new(),
+        };
+
+        match bot.receive_timeout(BOT_START_TIMEOUT)? {
+            BotMessage::Info { name, .. } => bot.name = name,
+            _ => return Err(BotError::Invalid(String::from("expected info"))),
+        }
+
+        bot.send(&FrontendMessage::Rules {})?;
+
+        match bot.receive_timeout(BOT_START_TIMEOUT)? {
+            BotMessage::Ready => Ok(bot),
+            BotMessage::Error { reason } => Err(BotError::Rejected(reason)),
+            _ => Err(BotError::Invalid(String::from("expected ready"))),
+        }
+    }
+
+    /// Start a bot on a background thread, so the game can keep running while it waits for the bot to be
+    /// ready.
+    pub fn spawn_in_background(command: String, args: Vec<String>) -> BotStart {
+        let (sender, bot) = mpsc::channel();
+
+        thread::spawn(move || {
+            let _ = sender.send(Self::spawn(&command, &args));
+        });
+
+        BotStart { bot }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn send(&mut self, message: &FrontendMessage) -> Result<(), BotError> {
+        let line =
+            serde_json::to_string(message).map_err(|error| BotError::Invalid(error.to_string()))?;
+
+        writeln!(self.stdin, "{}", line)?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    /// Get the next message from the bot, if there is one, without waiting.
+    pub fn try_receive(&self) -> Result<Option<BotMessage>, BotError> {
+        match self.messages.try_recv() {
+            Ok(message) => message.map(Some).map_err(BotError::Invalid),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(BotError::Exited),
+        }
+    }
+
+    pub fn receive_timeout(&self, timeout: Duration) -> Result<BotMessage, BotError> {
+        match self.messages.recv_timeout(timeout) {
+            Ok(message) => message.map_err(BotError::Invalid),
+            Err(RecvTimeoutError::Timeout) => Err(BotError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(BotError::Exited),
+        }
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        let _ = self.send(&FrontendMessage::Quit);
+
+        let Some(mut child) = self.child.take() else {
+            return;
+        };
+
+        // The bot is waited for on a background thread, so dropping it doesn't hold up the game.
+        thread::spawn(move || {
+            let quit_at = Instant::now();
+
+            while quit_at.elapsed() < BOT_QUIT_TIMEOUT {
+                if let Ok(Some(_)) = child.try_wait() {
+                    return;
+                }
+
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+    }
+}
+
+/// A bot being started in the background. Dropping this before it's ready quits the bot once it has started.
+pub struct BotStart {
+    bot: Receiver<Result<Bot, BotError>>,
+}
+
+impl BotStart {
+    /// Check whether the bot is ready, without blocking.
+    pub fn poll(&self) -> Result<Option<Bot>, BotError> {
+        match self.bot.try_recv() {
+            Ok(result) => result.map(Some),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(BotError::Exited),
+        }
+    }
+}
+
+/// What the bot thinks the game looks like after the last move it was told about.
+struct ExpectedState {
+    grid: Grid,
+    hold: Option<&'static str>,
+    queue: Vec<&'static str>,
+}
+
+/// Plays a game using a bot's suggestions, turning each suggested move into the inputs that make it.
+pub struct BotPlayer {
+    bot: Bot,
+    // Inputs for the move being made, to be fed to the game one tick at a time
+    inputs: VecDeque<GameInput>,
+    is_started: bool,
+    is_waiting_for_suggestion: bool,
+    // None when the bot needs to be started again, because the game has changed in a way it can't know about
+    // (e.g. garbage was added)
+    expected_state: Option<ExpectedState>,
+    error: Option<BotError>,
+}
+
+impl BotPlayer {
+    pub fn new(bot: Bot) -> Self {
+        Self {
+            bot,
+            inputs: VecDeque::new(),
+            is_started: false,
+            is_waiting_for_suggestion: false,
+            expected_state: None,
+            error: None,
+        }
+    }
+
+    pub fn get_bot(&self) -> &Bot {
+        &self.bot
+    }
+
+    /// Whether the bot has been asked for a move, and hasn't suggested one yet.
+    pub fn get_is_thinking(&self) -> bool {
+        self.is_waiting_for_suggestion
+    }
+
+    /// Get the error that stopped the bot from playing, if there was one.
+    pub fn get_error(&self) -> Option<&BotError> {
+        self.error.as_ref()
+    }
+
+    /// Get the input for the next tick of the given game. The bot keeps the game waiting while it's thinking,
+    /// and stops playing altogether if it runs into an error.
+    pub fn get_input(&mut self, game_state: &GameState) -> GameInput {
+        if let Some(input) = self.inputs.pop_front() {
+            return input;
+        }
+
+        if self.error.is_some()
+            || game_state.get_is_game_over()
+            || !game_state.get_is_piece_active()
+        {
+            return GameInput::default();
+        }
+
+        if let Err(error) = self.update(game_state) {
+            self.error = Some(error);
+        }
+
+        self.inputs.pop_front().unwrap_or_default()
+    }
+
+    fn update(&mut self, game_state: &GameState) -> Result<(), BotError> {
+        if !self.is_waiting_for_suggestion {
+            self.sync(game_state)?;
+            self.bot.send(&FrontendMessage::Suggest)?;
+            self.is_waiting_for_suggestion = true;
+        }
+
+        let Some(message) = self.bot.try_receive()? else {
+            return Ok(());
+        };
+
+        let BotMessage::Suggestion { moves } = message else {
+            return Err(BotError::Invalid(String::from("expected suggestion")));
+        };
+
+        self.is_waiting_for_suggestion = false;
+
+        for tbp_move in moves {
+            if let Some((inputs, expected_state)) = self.plan_move(game_state, &tbp_move) {
+                self.bot.send(&FrontendMessage::Play { tbp_move })?;
+                self.inputs.extend(inputs);
+                self.expected_state = Some(expected_state);
+                return Ok(());
+            }
+        }
+
+        // None of the suggestions can be made here, so just drop the piece, and start the bot over on the next
+        // one.
+        self.inputs.push_back(GameInput {
+            hard_drop: true,
+            ..Default::default()
+        });
+        self.expected_state = None;
+
+        Ok(())
+    }
+
+    /// Bring the bot up to date with the game, by telling it about newly revealed pieces if it's been
+    /// following along, or starting it over if it hasn't.
+    fn sync(&mut self, game_state: &GameState) -> Result<(), BotError> {
+        let hold = game_state.get_held_piece().map(|piece| piece.name);
+        let queue = get_queue(game_state);
+
+        if let Some(expected_state) = &self.expected_state {
+            if expected_state.hold == hold
+                && queue.starts_with(&expected_state.queue)
+                && is_same_shape(&expected_state.grid, game_state.get_grid_locked())
+            {
+                for piece in &queue[expected_state.queue.len()..] {
+                    self.bot.send(&FrontendMessage::NewPiece {
+                        piece: String::from(*piece),
+                    })?;
+                }
+
+                return Ok(());
+            }
+        }
+
+        if self.is_started {
+            self.bot.send(&FrontendMessage::Stop)?;
+        }
+
+        self.bot.send(&FrontendMessage::Start {
+            hold: hold.map(String::from),
+            queue: queue.iter().map(|piece| String::from(*piece)).collect(),
+            combo: game_state.get_combo() as u32,
+            back_to_back: game_state.get_is_back_to_back(),
+            board: to_board(game_state.get_grid_locked()),
+        })?;
+
+        self.is_started = true;
+        Ok(())
+    }
+
+    /// Work out the inputs for a suggested move, along with what the game will look like to the bot once
+    /// it's been made. None if the move isn't possible.
+    fn plan_move(
+        &self,
+        game_state: &GameState,
+        tbp_move: &TbpMove,
+    ) -> Option<(Vec<GameInput>, ExpectedState)> {
+        let (piece, position) = from_piece_location(&tbp_move.location)?;
+        let mut hold = game_state.get_held_piece().map(|piece| piece.name);
+        let mut queue = get_queue(game_state);
+
+        // Moves for anything other than the active piece have to hold first, to get to that piece.
+        let is_hold = piece.name != queue[0];
+        let inputs = plan_inputs(game_state, position, is_hold)?;
+
+        if is_hold {
+            let swapped_piece = queue.remove(0);
+
+            if hold.is_none() {
+                queue.remove(0);
+            }
+
+            hold = Some(swapped_piece);
+        } else {
+            queue.remove(0);
+        }
+
+        let mut grid = *game_state.get_grid_locked();
+        position.place(&mut grid, &piece);
+
+        Some((inputs, ExpectedState { grid, hold, queue }))
+    }
+}
+
+/// Get the active piece followed by the previews, as the bot sees them.
+fn get_queue(game_state: &GameState) -> Vec<&'static str> {
+    std::iter::once(game_state.get_active_piece())
+        .chain(game_state.get_piece_previews())
+        .map(|piece| piece.name)
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! Reading the little-endian binary formats used by replays and network messages.
+
+/// Why a value couldn't be read. Each format maps this into its own error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ReadError {
+    /// There weren't enough bytes left for the value.
+    UnexpectedEnd,
+    /// A string wasn't valid UTF-8.
+    MalformedString,
+}
+
+/// Reads little-endian values from a byte slice, failing if it runs out.
+pub(crate) struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(crate) fn take(&mut self, count: usize) -> Result<&'a [u8], ReadError> {
+        if self.bytes.len() < count {
+            return Err(ReadError::UnexpectedEnd);
+        }
+
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], ReadError> {
+        let (taken, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .ok_or(ReadError::UnexpectedEnd)?;
+
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, ReadError> {
+        Ok(self.take_array::<1>()?[0])
+    }
+
+    pub(crate) fn read_u16(&mut self) -> Result<u16, ReadError> {
+        Ok(u16::from_le_bytes(self.take_array()?))
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, ReadError> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, ReadError> {
+        Ok(u64::from_le_bytes(self.take_array()?))
+    }
+
+    /// Read a string prefixed by its length in bytes, as a u8.
+    pub(crate) fn read_str(&mut self) -> Result<&'a str, ReadError> {
+        let length = self.read_u8()? as usize;
+        std::str::from_utf8(self.take(length)?).map_err(|_| ReadError::MalformedString)
+    }
+}
@@ -0,0 +1,169 @@
+//! Finesse: placing each piece with as few key presses as possible. Every locked piece is judged against the
+//! fewest shift and rotation presses that could have put it in the same place, and any extra presses are a
+//! fault.
+
+use crate::game_state::{GameState, SPAWN_ROW};
+use crate::grid::Grid;
+use crate::piece::{pieces, Piece};
+use crate::placement::{find_placements, Move, PiecePosition};
+use crate::storage::{read_records, write_records};
+use rand::Rng;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const FINESSE_PATH: &str = "./.finesse";
+
+/// How a locked piece was judged.
+#[derive(Clone, Copy, Debug)]
+pub struct FinesseResult {
+    pub piece: Piece,
+    /// The number of shift and rotation presses used to place the piece
+    pub inputs: usize,
+    /// The fewest presses that could have placed the piece in the same spot
+    pub min_inputs: usize,
+    /// Whether the piece was placed on the trainer's target. Always true outside of the trainer.
+    pub is_on_target: bool,
+}
+
+impl FinesseResult {
+    pub fn get_is_fault(&self) -> bool {
+        self.inputs > self.min_inputs || !self.is_on_target
+    }
+}
+
+/// Get the index of a piece in pieces::ALL, which per-piece counts are kept in the same order as.
+pub fn get_piece_index(piece: &Piece) -> usize {
+    pieces::ALL
+        .iter()
+        .position(|other| other.name == piece.name)
+        .unwrap_or(0)
+}
+
+pub fn get_spawn_position(piece: &Piece) -> PiecePosition {
+    PiecePosition {
+        row: SPAWN_ROW,
+        col: piece.get_initial_col(),
+        orientation: 0,
+    }
+}
+
+/// Get the cells a piece would cover if it were dropped onto an empty grid from the given position.
+fn get_dropped_cells(piece: &Piece, position: PiecePosition) -> Vec<(isize, isize)> {
+    let mut cells = position
+        .get_landing_position(&Grid::new(), piece)
+        .get_cells(piece);
+    cells.sort();
+    cells
+}
+
+/// Get the fewest shift and rotation presses that move a piece from where it spawns to anywhere that drops into
+/// the same cells as the target, on an empty grid. Holding a shift until the piece reaches the wall counts as a
+/// single press.
+pub fn get_min_inputs(piece: &Piece, target: PiecePosition) -> usize {
+    let grid = Grid::new();
+    let target_cells = get_dropped_cells(piece, target);
+    let spawn_position = get_spawn_position(piece);
+    let mut visited = HashSet::from([spawn_position]);
+    let mut queue = VecDeque::from([(spawn_position, 0)]);
+
+    while let Some((position, inputs)) = queue.pop_front() {
+        if get_dropped_cells(piece, position) == target_cells {
+            return inputs;
+        }
+
+        let mut next_positions: Vec<PiecePosition> = [
+            Move::ShiftLeft,
+            Move::ShiftRight,
+            Move::RotateClockwise,
+            Move::RotateCounterClockwise,
+            Move::Rotate180,
+        ]
+        .iter()
+        .filter_map(|next_move| next_move.apply(&grid, piece, position))
+        .collect();
+
+        for shift in [Move::ShiftLeft, Move::ShiftRight] {
+            let mut wall_position = position;
+
+            while let Some(next_position) = shift.apply(&grid, piece, wall_position) {
+                wall_position = next_position;
+            }
+
+            next_positions.push(wall_position);
+        }
+
+        for next_position in next_positions {
+            if visited.insert(next_position) {
+                queue.push_back((next_position, inputs + 1));
+            }
+        }
+    }
+
+    // Every placement on an empty grid can be reached, so this shouldn't happen. If it does, nothing is a fault.
+    usize::MAX
+}
+
+/// Pick a random placement for the piece on an empty grid, for the trainer to aim for.
+pub fn get_random_target(piece: &Piece, rng: &mut impl Rng) -> PiecePosition {
+    let spawn_position = get_spawn_position(piece);
+    let placements = find_placements(&Grid::new(), piece, spawn_position);
+
+    placements
+        .get(rng.gen_range(0..placements.len().max(1)))
+        .copied()
+        .unwrap_or_else(|| spawn_position.get_landing_position(&Grid::new(), piece))
+}
+
+/// Whether two positions of a piece would drop into the same cells, on an empty grid.
+pub fn get_is_same_placement(piece: &Piece, a: PiecePosition, b: PiecePosition) -> bool {
+    get_dropped_cells(piece, a) == get_dropped_cells(piece, b)
+}
+
+/// Finesse faults and pieces judged for each kind of piece, over every game played. Counts are kept in the same
+/// order as pieces::ALL.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FinesseRecord {
+    pub faults: [u64; 7],
+    pub pieces: [u64; 7],
+}
+
+impl FinesseRecord {
+    pub fn load() -> Self {
+        let records = read_records(FINESSE_PATH);
+        let mut record = Self::default();
+
+        for (index, piece) in pieces::ALL.iter().enumerate() {
+            record.faults[index] = records
+                .get(&format!("{}_faults", piece.name))
+                .copied()
+                .unwrap_or(0);
+            record.pieces[index] = records
+                .get(&format!("{}_pieces", piece.name))
+                .copied()
+                .unwrap_or(0);
+        }
+
+        record
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut records = HashMap::new();
+
+        for (index, piece) in pieces::ALL.iter().enumerate() {
+            records.insert(format!("{}_faults", piece.name), self.faults[index]);
+            records.insert(format!("{}_pieces", piece.name), self.pieces[index]);
+        }
+
+        write_records(FINESSE_PATH, &records)
+    }
+
+    /// Add the counts from a finished game.
+    pub fn add_game(&mut self, game_state: &GameState) {
+        let faults = game_state.get_finesse_faults_by_piece();
+        let pieces_judged = game_state.get_finesse_pieces_by_piece();
+
+        for index in 0..pieces::ALL.len() {
+            self.faults[index] += faults[index] as u64;
+            self.pieces[index] += pieces_judged[index] as u64;
+        }
+    }
+}
@@ -0,0 +1,173 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameMode {
+    /// Endless play, for score.
+    Marathon,
+    /// Clear 40 lines as fast as possible.
+    Sprint,
+    /// Score as much as possible in 2 minutes.
+    Ultra,
+    /// Reach level 999, as gravity builds up to 20G. Based on the arcade TGM games.
+    Master,
+    /// Dig through the given number of rows of garbage as fast as possible.
+    Cheese(usize),
+    /// Place each piece on a target with as few key presses as possible. Pieces vanish once they're placed.
+    Finesse,
+}
+
+/// The number of rows that have to be cleared to finish a Sprint.
+pub const SPRINT_ROWS: usize = 40;
+
+/// The number of ticks an Ultra game lasts. 2 minutes at 60 ticks per second.
+pub const ULTRA_TICKS: usize = 120 * 60;
+
+/// The most garbage rows that are on the stack at once in a Cheese race. Longer races top the garbage back up
+/// to this as it's cleared.
+pub const CHEESE_VISIBLE_ROWS: usize = 10;
+
+/// The number of pieces placed in a session of the finesse trainer.
+pub const FINESSE_TRAINER_PIECES: usize = 50;
+
+/// The level that finishes a Master game.
+pub const MASTER_MAX_LEVEL: usize = 999;
+
+/// Master gravity in 1/256ths of a row per tick, as (level, gravity) starting from that level. From TGM.
+const MASTER_GRAVITY: [(usize, usize); 30] = [
+    (0, 4),
+    (30, 6),
+    (35, 8),
+    (40, 10),
+    (50, 12),
+    (60, 16),
+    (70, 32),
+    (80, 48),
+    (90, 64),
+    (100, 80),
+    (120, 96),
+    (140, 112),
+    (160, 128),
+    (170, 144),
+    (200, 4),
+    (220, 32),
+    (230, 64),
+    (233, 96),
+    (236, 128),
+    (239, 160),
+    (243, 192),
+    (247, 224),
+    (251, 256), // 1G
+    (300, 512),
+    (330, 768),
+    (360, 1024),
+    (400, 1280),
+    (420, 1024),
+    (450, 768),
+    (500, 5120), // 20G
+];
+
+/// Master delays in ticks, as (level, ARE, line clear delay, lock delay) starting from that level. Based on TGM2.
+const MASTER_DELAYS: [(usize, isize, isize, isize); 6] = [
+    (0, 25, 40, 30),
+    (500, 25, 25, 30),
+    (600, 25, 16, 30),
+    (700, 16, 12, 30),
+    (800, 12, 6, 30),
+    (900, 12, 6, 17),
+];
+
+/// Get the Master gravity at the given level, in rows per tick.
+pub fn get_master_gravity(level: usize) -> f32 {
+    let (_, gravity) = MASTER_GRAVITY
+        .iter()
+        .rev()
+        .find(|(start_level, _)| level >= *start_level)
+        .unwrap_or(&MASTER_GRAVITY[0]);
+
+    *gravity as f32 / 256.0
+}
+
+/// Get the Master delays at the given level, as (ARE, line clear delay, lock delay).
+pub fn get_master_delays(level: usize) -> (isize, isize, isize) {
+    let (_, are, line_clear_delay, lock_delay) = MASTER_DELAYS
+        .iter()
+        .rev()
+        .find(|(start_level, ..)| level >= *start_level)
+        .unwrap_or(&MASTER_DELAYS[0]);
+
+    (*are, *line_clear_delay, *lock_delay)
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 8] = [
+        GameMode::Marathon,
+        GameMode::Sprint,
+        GameMode::Ultra,
+        GameMode::Master,
+        GameMode::Cheese(10),
+        GameMode::Cheese(18),
+        GameMode::Cheese(100),
+        GameMode::Finesse,
+    ];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Sprint => "Sprint (40 Lines)",
+            GameMode::Ultra => "Ultra (2 Min)",
+            GameMode::Master => "Master (20G)",
+            GameMode::Cheese(10) => "Cheese (10 Lines)",
+            GameMode::Cheese(18) => "Cheese (18 Lines)",
+            GameMode::Cheese(100) => "Cheese (100 Lines)",
+            GameMode::Cheese(_) => "Cheese",
+            GameMode::Finesse => "Finesse Trainer",
+        }
+    }
+
+    pub fn get_short_label(&self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Sprint => "Sprint",
+            GameMode::Ultra => "Ultra",
+            GameMode::Master => "Master",
+            GameMode::Cheese(10) => "Cheese 10",
+            GameMode::Cheese(18) => "Cheese 18",
+            GameMode::Cheese(100) => "Cheese 100",
+            GameMode::Cheese(_) => "Cheese",
+            GameMode::Finesse => "Finesse",
+        }
+    }
+
+    pub fn get_id(&self) -> &'static str {
+        match self {
+            GameMode::Marathon => "marathon",
+            GameMode::Sprint => "sprint",
+            GameMode::Ultra => "ultra",
+            GameMode::Master => "master",
+            GameMode::Cheese(10) => "cheese_10",
+            GameMode::Cheese(18) => "cheese_18",
+            GameMode::Cheese(100) => "cheese_100",
+            GameMode::Cheese(_) => "cheese",
+            GameMode::Finesse => "finesse",
+        }
+    }
+
+    /// Whether rotation and hold keys pressed or held during the delays apply to the next piece as it spawns
+    /// (IRS and IHS). The race modes and the finesse trainer leave this off, so an early press can't misdrop.
+    pub fn get_has_initial_actions(&self) -> bool {
+        match self {
+            GameMode::Marathon | GameMode::Master => true,
+            GameMode::Sprint | GameMode::Ultra | GameMode::Cheese(_) | GameMode::Finesse => false,
+        }
+    }
+
+    /// Whether results in this mode are ranked by the time taken to finish, fastest first, rather than by score.
+    pub fn get_is_timed(&self) -> bool {
+        match self {
+            GameMode::Sprint | GameMode::Cheese(_) => true,
+            GameMode::Marathon | GameMode::Ultra | GameMode::Master | GameMode::Finesse => false,
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.get_id() == id)
+    }
+}
@@ -0,0 +1,1539 @@
+use crate::{
+    attack::AttackTable,
+    finesse::{
+        get_is_same_placement, get_min_inputs, get_piece_index, get_random_target, FinesseResult,
+    },
+    game_mode::{
+        get_master_delays, get_master_gravity, GameMode, CHEESE_VISIBLE_ROWS,
+        FINESSE_TRAINER_PIECES, MASTER_MAX_LEVEL, SPRINT_ROWS, ULTRA_TICKS,
+    },
+    grid::{Grid, GRID_COUNT_COLS, GRID_COUNT_ROWS},
+    handling::{HandlingConfig, SoftDrop, FIXED_SOFT_DROP_GRAVITY},
+    piece::{BlockCanvas, Piece, Rotation},
+    placement::PiecePosition,
+    randomizer::{Randomizer, RandomizerKind},
+    replay::{Replay, RULES_VERSION},
+    scoring::{get_perfect_clear_points, ClearKind, SpinKind},
+    stats::GameStats,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
+
+pub const TICKS_PER_SECOND: f32 = 60.0;
+/// The row new pieces spawn in, at the top of their block canvas.
+pub const SPAWN_ROW: isize = 1;
+const CHECKPOINT_INTERVAL_TICKS: usize = 60;
+
+/// The state of every input for a single tick. Held inputs (soft drop, shifts and the ones ending in "held")
+/// should be true for as long as the key is held, while the rest should only be true on the tick the key was
+/// pressed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GameInput {
+    pub soft_drop: bool,
+    pub shift_left: bool,
+    pub shift_right: bool,
+    pub rotate_right: bool,
+    pub rotate_left: bool,
+    pub rotate_180: bool,
+    pub hard_drop: bool,
+    pub hold_piece: bool,
+    pub toggle_pause: bool,
+    // Whether the rotation and hold keys are down, as held inputs. These only matter as a piece spawns, where a
+    // key held since before the entry delay still rotates or holds it.
+    pub rotate_right_held: bool,
+    pub rotate_left_held: bool,
+    pub rotate_180_held: bool,
+    pub hold_piece_held: bool,
+}
+
+/// What the game is doing with the active piece. Pieces fall and lock, then the game waits out the line clear
+/// delay (if rows were filled) and the entry delay before spawning the next piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// The active piece is in the air.
+    Falling,
+    /// The active piece is resting on the stack, and will lock once the lock delay runs out.
+    Locking,
+    /// The filled rows are still on the stack, waiting for the line clear delay to run out.
+    LineClear,
+    /// There's no active piece, and the next one will appear once the entry delay (ARE) runs out.
+    Spawning,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ShiftDirection {
+    Left,
+    Right,
+    Neither,
+}
+
+#[derive(Clone)]
+pub struct GameState {
+    grid_locked: Grid,
+    grid_active: Grid,
+    grid_ghost: Grid,
+    phase: Phase,
+    // How long until the current line clear or entry delay ends
+    ticks_to_phase_end: isize,
+    // Rows that were filled by the last locked piece, and are waiting to be removed
+    clearing_rows: Vec<usize>,
+    // Garbage waiting to be pushed onto the bottom of the stack when the next piece locks, as (rows, hole column)
+    pending_garbage: Vec<(usize, usize)>,
+    attack_table: AttackTable,
+    // Garbage sent to the opponent that they haven't received yet
+    outgoing_garbage: usize,
+    // Picks the holes for Cheese and versus garbage. It's seeded from the game seed, but kept apart from the
+    // randomizer so the garbage doesn't change the pieces.
+    garbage_rng: ChaCha8Rng,
+    garbage_hole_col: Option<usize>,
+    garbage_rows_added: usize,
+    garbage_rows_cleared: usize,
+    // Rotation and hold inputs pressed during the delays, or held as it spawns, to be applied to the next piece
+    initial_rotation: Option<Rotation>,
+    is_initial_hold: bool,
+    mode: GameMode,
+    handling: HandlingConfig,
+    randomizer: Box<dyn Randomizer>,
+    randomizer_kind: RandomizerKind,
+    seed: u64,
+    active_piece: Piece,
+    score: usize,
+    tick: usize,
+    active_piece_col: isize,
+    active_piece_row: isize,
+    active_piece_orientation: usize,
+    ticks_to_next_row_inc: isize,
+    ticks_to_repeat: isize,
+    ticks_to_lock: isize,
+    lock_reset_moves_remaining: isize,
+    // The lowest row the active piece has reached. Only descending below this refills the lock reset moves, so
+    // pieces that can kick upward can't stall forever.
+    lowest_row_reached: isize,
+    shift_direction: ShiftDirection,
+    held_piece: Option<Piece>,
+    last_piece_swapped: bool,
+    rows_cleared: usize,
+    // The TGM-style level in Master mode, which goes up with every piece spawned and every row cleared
+    master_level: usize,
+    is_game_over: bool,
+    // Whether the game ended by reaching the mode's goal, rather than by topping out
+    is_goal_reached: bool,
+    is_paused: bool,
+    // Cached block canvas to avoid repeated allocations
+    cached_blocks: BlockCanvas,
+    cached_bounds_height: usize,
+    cached_bounds_width: usize,
+    // Dirty flag to track if piece state changed
+    piece_dirty: bool,
+    // Cached ghost row
+    cached_ghost_row: isize,
+    // The rotation and kick index used, if the last successful move of the active piece was a rotation
+    last_rotation_kick: Option<(Rotation, usize)>,
+    last_clear_kind: Option<ClearKind>,
+    // Whether the last line clear was difficult, so the next difficult clear gets the back-to-back bonus
+    back_to_back: bool,
+    // Number of consecutive locks that cleared rows, minus one. -1 if the last lock didn't clear any rows.
+    combo: isize,
+    last_clear_was_perfect: bool,
+    stats: GameStats,
+    // The input from the tick before, so held keys are only counted as pressed once
+    last_input: GameInput,
+    // Shift and rotation presses used on the active piece so far, and whether each shift was held on the last
+    // tick, so holding a shift only counts as one press
+    finesse_inputs: usize,
+    last_shift_inputs: (bool, bool),
+    // The placement the finesse trainer wants for the active piece, and the generator that picks it
+    finesse_target: Option<PiecePosition>,
+    finesse_rng: ChaCha8Rng,
+    last_finesse_result: Option<FinesseResult>,
+    // Pieces judged and finesse faults made, per piece in the same order as pieces::ALL
+    finesse_pieces_by_piece: [usize; 7],
+    finesse_faults_by_piece: [usize; 7],
+    // Shared with copies of the game until one of them records something, as it grows every tick
+    recording: Arc<Recording>,
+}
+
+/// What a game records for its replay.
+#[derive(Clone, Default)]
+struct Recording {
+    // Every input that advanced the game
+    inputs: Vec<GameInput>,
+    // Hashes of the game state at regular intervals, and at the end of the game, as (tick, hash). Replays use
+    // these to detect when playback has diverged from the original game.
+    checkpoints: Vec<(usize, u64)>,
+}
+
+impl GameState {
+    /// Create a new game whose pieces are dealt by the given kind of randomizer. The piece sequence is
+    /// entirely determined by the randomizer kind and seed.
+    pub fn new(
+        mode: GameMode,
+        randomizer_kind: RandomizerKind,
+        seed: u64,
+        handling: HandlingConfig,
+    ) -> Self {
+        let grid_locked = Grid::new();
+        let grid_active = Grid::new();
+        let grid_ghost = Grid::new();
+        let mut randomizer = randomizer_kind.create(seed);
+        let mut garbage_rng = ChaCha8Rng::seed_from_u64(seed);
+        garbage_rng.set_stream(1);
+        let mut finesse_rng = ChaCha8Rng::seed_from_u64(seed);
+        finesse_rng.set_stream(2);
+        let active_piece = randomizer.next();
+        let score: usize = 0;
+        let tick: usize = 0;
+
+        // Initialize cached blocks
+        let (cached_blocks, cached_bounds_height, cached_bounds_width) = active_piece.get_blocks(0);
+
+        let mut game_state = Self {
+            grid_locked,
+            grid_active,
+            grid_ghost,
+            phase: Phase::Falling,
+            ticks_to_phase_end: 0,
+            clearing_rows: Vec::new(),
+            pending_garbage: Vec::new(),
+            attack_table: AttackTable::default(),
+            outgoing_garbage: 0,
+            garbage_rng,
+            garbage_hole_col: None,
+            garbage_rows_added: 0,
+            garbage_rows_cleared: 0,
+            initial_rotation: None,
+            is_initial_hold: false,
+            mode,
+            handling,
+            randomizer,
+            randomizer_kind,
+            seed,
+            active_piece,
+            score,
+            tick,
+            // The rest of the active piece state is set up by reset_piece_state, below.
+            active_piece_col: 0,
+            active_piece_row: 0,
+            active_piece_orientation: 0,
+            ticks_to_next_row_inc: 0,
+            ticks_to_repeat: handling.das_ticks,
+            ticks_to_lock: 0,
+            lock_reset_moves_remaining: 0,
+            lowest_row_reached: 0,
+            shift_direction: ShiftDirection::Neither,
+            held_piece: None,
+            last_piece_swapped: false,
+            rows_cleared: 0,
+            master_level: 0,
+            is_game_over: false,
+            is_goal_reached: false,
+            is_paused: false,
+            cached_blocks,
+            cached_bounds_height,
+            cached_bounds_width,
+            piece_dirty: true,
+            cached_ghost_row: 0,
+            last_rotation_kick: None,
+            last_clear_kind: None,
+            back_to_back: false,
+            combo: -1,
+            last_clear_was_perfect: false,
+            stats: GameStats::default(),
+            last_input: GameInput::default(),
+            finesse_inputs: 0,
+            last_shift_inputs: (false, false),
+            finesse_target: None,
+            finesse_rng,
+            last_finesse_result: None,
+            finesse_pieces_by_piece: [0; 7],
+            finesse_faults_by_piece: [0; 7],
+            recording: Arc::default(),
+        };
+
+        game_state.reset_piece_state();
+
+        // Cheese races start with the garbage already on the stack.
+        game_state.top_up_cheese();
+        game_state.apply_pending_garbage();
+
+        game_state
+    }
+
+    fn get_new_ticks_to_next_row_inc(&self) -> isize {
+        (1.0 / self.get_gravity()).ceil() as isize
+    }
+
+    fn reset_piece_state(&mut self) {
+        self.phase = Phase::Falling;
+        self.active_piece_orientation = 0;
+        self.active_piece_col = self.active_piece.get_initial_col();
+        self.active_piece_row = SPAWN_ROW;
+        self.ticks_to_next_row_inc = self.get_new_ticks_to_next_row_inc();
+        self.last_piece_swapped = false;
+        self.ticks_to_lock = self.get_lock_delay_ticks();
+        self.lock_reset_moves_remaining = self.handling.reset_moves;
+        self.lowest_row_reached = self.active_piece_row;
+        self.last_rotation_kick = None;
+        self.finesse_inputs = 0;
+        // A shift that's still held from the last piece counts as a press for this one.
+        self.last_shift_inputs = (false, false);
+
+        if self.mode == GameMode::Finesse {
+            self.finesse_target =
+                Some(get_random_target(&self.active_piece, &mut self.finesse_rng));
+        }
+
+        self.refresh_cached_blocks();
+    }
+
+    fn refresh_cached_blocks(&mut self) {
+        let (blocks, height, width) = self.active_piece.get_blocks(self.active_piece_orientation);
+        self.cached_blocks = blocks;
+        self.cached_bounds_height = height;
+        self.cached_bounds_width = width;
+        self.piece_dirty = true;
+    }
+
+    fn try_reset_lock_delay_for_move(&mut self) {
+        if self.lock_reset_moves_remaining > 0 {
+            self.lock_reset_moves_remaining -= 1;
+            self.ticks_to_lock = self.get_lock_delay_ticks();
+        }
+    }
+
+    fn get_lock_delay_ticks(&self) -> isize {
+        match self.mode {
+            GameMode::Master => get_master_delays(self.master_level).2,
+            _ => self.handling.lock_delay_ticks,
+        }
+    }
+
+    fn get_are_ticks(&self) -> isize {
+        match self.mode {
+            GameMode::Master => get_master_delays(self.master_level).0,
+            _ => self.handling.are_ticks,
+        }
+    }
+
+    fn get_line_clear_delay_ticks(&self) -> isize {
+        match self.mode {
+            GameMode::Master => get_master_delays(self.master_level).1,
+            _ => self.handling.line_clear_delay_ticks,
+        }
+    }
+
+    /// In Master mode, go up a level for a piece spawning after the first. Spawning pieces can't take the level
+    /// past the last level of a section (x99), only clearing rows can.
+    fn increase_master_level_for_spawn(&mut self) {
+        if self.mode != GameMode::Master {
+            return;
+        }
+
+        let is_section_stop =
+            self.master_level % 100 == 99 || self.master_level == MASTER_MAX_LEVEL - 1;
+
+        if !is_section_stop {
+            self.master_level += 1;
+        }
+    }
+
+    /// In Master mode, go up a level for every row cleared.
+    fn increase_master_level_for_rows(&mut self, rows_cleared: usize) {
+        if self.mode == GameMode::Master {
+            self.master_level = (self.master_level + rows_cleared).min(MASTER_MAX_LEVEL);
+        }
+    }
+
+    fn end_game(&mut self) {
+        self.is_game_over = true;
+    }
+
+    /// Check whether the mode's goal has been reached, and end the game if so.
+    fn check_for_goal(&mut self) {
+        let is_goal_reached = match self.mode {
+            GameMode::Marathon => false,
+            GameMode::Sprint => self.rows_cleared >= SPRINT_ROWS,
+            GameMode::Ultra => self.tick >= ULTRA_TICKS,
+            GameMode::Master => self.master_level >= MASTER_MAX_LEVEL,
+            GameMode::Cheese(total_rows) => self.garbage_rows_cleared >= total_rows,
+            GameMode::Finesse => self.get_finesse_pieces() >= FINESSE_TRAINER_PIECES,
+        };
+
+        if is_goal_reached {
+            self.is_goal_reached = true;
+            self.end_game();
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.is_paused = !self.is_paused;
+    }
+
+    /// Check if the active piece, if it were locked, would be entirely outside the visible bounds of the playfield.
+    fn check_for_lock_out(&self) -> bool {
+        self.grid_locked.invisible_check(
+            self.active_piece_row,
+            &self.cached_blocks,
+            self.cached_bounds_height,
+            self.cached_bounds_width,
+        )
+    }
+
+    fn set_active_piece_and_reset_state(&mut self, next_active_piece: Piece) {
+        self.active_piece = next_active_piece;
+        self.reset_piece_state();
+
+        // Spawn rotated if a rotation was pressed during the delays, unless the rotated piece doesn't fit. Unlike
+        // a normal rotation, there are no kicks.
+        if let Some(rotation) = self.initial_rotation.take() {
+            let orientation = rotation.apply(self.active_piece_orientation);
+
+            if !self.collide(None, None, Some(orientation)) {
+                self.active_piece_orientation = orientation;
+                self.finesse_inputs += 1;
+                self.refresh_cached_blocks();
+            }
+        }
+
+        // Check for a piece spawned overlapping at least one block in the playfield (Block Out)
+        let is_block_out = self.collide(None, None, None);
+
+        if is_block_out {
+            return self.end_game();
+        }
+
+        // Faster than 1G, gravity applies on the tick the piece spawns, so at 20G it appears on the stack.
+        let gravity = self.get_gravity();
+
+        if gravity > 1.0 {
+            self.drop_active_piece(gravity.floor() as usize);
+        }
+    }
+
+    fn next_piece(&mut self) {
+        let next_active_piece = self.randomizer.next();
+        self.set_active_piece_and_reset_state(next_active_piece);
+    }
+
+    fn swap_active_piece(&mut self) {
+        // Return early if the last piece was already swapped, as you can only swap once before having
+        // to land a piece.
+        if self.last_piece_swapped {
+            return;
+        }
+
+        if let Some(held_piece) = self.held_piece {
+            self.held_piece = Some(self.active_piece);
+            self.set_active_piece_and_reset_state(held_piece);
+        } else {
+            self.held_piece = Some(self.active_piece);
+            self.next_piece();
+        }
+
+        self.last_piece_swapped = true;
+    }
+
+    /// Check whether the active piece, in its current position, is in a T-spin, using the 3-corner test.
+    /// This must be called before the active piece is locked.
+    fn get_spin_kind(&self) -> SpinKind {
+        let Some((rotation, kick_index)) = self.last_rotation_kick else {
+            return SpinKind::None;
+        };
+
+        if self.active_piece.name != "T" {
+            return SpinKind::None;
+        }
+
+        // The T piece's center is always at (1, 1) in its canvas, so its corners are at the corners of the
+        // canvas. Corners are ordered top-left, top-right, bottom-right, bottom-left.
+        let row = self.active_piece_row;
+        let col = self.active_piece_col;
+        let corners = [
+            self.grid_locked.is_cell_occupied(row, col),
+            self.grid_locked.is_cell_occupied(row, col + 2),
+            self.grid_locked.is_cell_occupied(row + 2, col + 2),
+            self.grid_locked.is_cell_occupied(row + 2, col),
+        ];
+
+        let occupied_count = corners.iter().filter(|&&corner| corner).count();
+        if occupied_count < 3 {
+            return SpinKind::None;
+        }
+
+        // The two "front" corners are the ones on either side of the T's point.
+        let front_a = corners[self.active_piece_orientation];
+        let front_b = corners[(self.active_piece_orientation + 1) % 4];
+
+        // The last kick test of a quarter turn (the "TST" kick) always counts as a full T-spin.
+        let is_last_kick = rotation != Rotation::Half && kick_index == 4;
+
+        if (front_a && front_b) || is_last_kick {
+            SpinKind::Full
+        } else {
+            SpinKind::Mini
+        }
+    }
+
+    fn lock_active_piece_and_get_next(&mut self) {
+        if self.check_for_lock_out() {
+            self.end_game();
+            return;
+        }
+
+        self.stats.pieces_placed += 1;
+        self.judge_finesse();
+
+        // The trainer only cares about where each piece goes, so pieces vanish once they've been judged, and the
+        // next target is always on an empty grid.
+        if self.mode == GameMode::Finesse {
+            self.piece_dirty = true;
+            self.check_for_goal();
+
+            if !self.is_game_over {
+                self.start_spawning();
+            }
+
+            return;
+        }
+
+        let spin = self.get_spin_kind();
+
+        self.grid_locked.set_cells(
+            self.active_piece_row,
+            self.active_piece_col,
+            &self.cached_blocks,
+            self.cached_bounds_height,
+            self.cached_bounds_width,
+        );
+
+        // The active piece is gone until the next one spawns.
+        self.piece_dirty = true;
+        self.update_score_for_filled_rows(spin);
+        self.increase_master_level_for_rows(self.clearing_rows.len());
+        self.check_for_goal();
+
+        if self.is_game_over {
+            return;
+        }
+
+        self.start_line_clear();
+    }
+
+    /// Compare the presses used to place the active piece against the fewest it could have taken. Pieces that
+    /// couldn't have been dropped straight into place from where they spawn (e.g. tucks and spins) aren't judged.
+    fn judge_finesse(&mut self) {
+        let piece = self.active_piece;
+        let position = self.get_active_piece_position();
+        let drop_position = PiecePosition {
+            row: SPAWN_ROW,
+            ..position
+        };
+
+        if drop_position.collides(&self.grid_locked, &piece)
+            || drop_position.get_landing_position(&self.grid_locked, &piece) != position
+        {
+            self.last_finesse_result = None;
+            return;
+        }
+
+        let result = FinesseResult {
+            piece,
+            inputs: self.finesse_inputs,
+            min_inputs: get_min_inputs(&piece, position),
+            is_on_target: self
+                .finesse_target
+                .is_none_or(|target| get_is_same_placement(&piece, position, target)),
+        };
+
+        let index = get_piece_index(&piece);
+        self.finesse_pieces_by_piece[index] += 1;
+
+        if result.get_is_fault() {
+            self.finesse_faults_by_piece[index] += 1;
+        }
+
+        self.last_finesse_result = Some(result);
+    }
+
+    /// Wait out the line clear delay if the last piece filled any rows, or go straight to spawning if not.
+    fn start_line_clear(&mut self) {
+        if self.clearing_rows.is_empty() {
+            return self.start_spawning();
+        }
+
+        if self.get_line_clear_delay_ticks() > 0 {
+            self.phase = Phase::LineClear;
+            self.ticks_to_phase_end = self.get_line_clear_delay_ticks();
+        } else {
+            self.collapse_cleared_rows();
+            self.start_spawning();
+        }
+    }
+
+    /// Push all of the pending garbage onto the bottom of the stack. The game is over if that pushes any blocks
+    /// off the top.
+    fn apply_pending_garbage(&mut self) {
+        for (rows, hole_col) in std::mem::take(&mut self.pending_garbage) {
+            if self.grid_locked.insert_garbage(rows, hole_col) {
+                self.end_game();
+            }
+        }
+    }
+
+    /// In a Cheese race, queue up more garbage to replace any that was cleared, until the race's total has been
+    /// added. Every row has its hole in a different column from the row below it.
+    fn top_up_cheese(&mut self) {
+        let GameMode::Cheese(total_rows) = self.mode else {
+            return;
+        };
+
+        let rows_on_stack = self.garbage_rows_added - self.garbage_rows_cleared;
+        let rows_to_add =
+            (CHEESE_VISIBLE_ROWS - rows_on_stack).min(total_rows - self.garbage_rows_added);
+
+        for _ in 0..rows_to_add {
+            let hole_col = match self.garbage_hole_col {
+                Some(last_hole_col) => {
+                    let hole_col = self.garbage_rng.gen_range(0..GRID_COUNT_COLS - 1);
+
+                    if hole_col >= last_hole_col {
+                        hole_col + 1
+                    } else {
+                        hole_col
+                    }
+                }
+                None => self.garbage_rng.gen_range(0..GRID_COUNT_COLS),
+            };
+
+            self.queue_garbage(1, hole_col);
+            self.garbage_hole_col = Some(hole_col);
+            self.garbage_rows_added += 1;
+        }
+    }
+
+    /// Send garbage to the opponent. It cancels out pending garbage first, oldest first, and only what's left
+    /// over is actually sent.
+    fn send_attack(&mut self, rows: usize) {
+        let mut rows = rows;
+
+        while rows > 0 {
+            let Some((pending_rows, _)) = self.pending_garbage.first_mut() else {
+                break;
+            };
+
+            let cancelled_rows = rows.min(*pending_rows);
+            *pending_rows -= cancelled_rows;
+            rows -= cancelled_rows;
+
+            if *pending_rows == 0 {
+                self.pending_garbage.remove(0);
+            }
+        }
+
+        self.outgoing_garbage += rows;
+    }
+
+    /// Take the garbage that's been sent since the last time this was called, to pass on to the opponent.
+    pub fn take_outgoing_garbage(&mut self) -> usize {
+        std::mem::take(&mut self.outgoing_garbage)
+    }
+
+    /// Queue rows of garbage sent by the opponent, with the hole in a random column.
+    pub fn receive_garbage(&mut self, rows: usize) {
+        let hole_col = self.garbage_rng.gen_range(0..GRID_COUNT_COLS);
+        self.queue_garbage(rows, hole_col);
+    }
+
+    pub fn set_attack_table(&mut self, attack_table: AttackTable) {
+        self.attack_table = attack_table;
+    }
+
+    /// Queue rows of garbage with a hole in the given column, to be added to the bottom of the stack when the
+    /// next piece locks.
+    pub fn queue_garbage(&mut self, rows: usize, hole_col: usize) {
+        if rows > 0 {
+            self.pending_garbage
+                .push((rows, hole_col.min(GRID_COUNT_COLS - 1)));
+        }
+    }
+
+    fn collapse_cleared_rows(&mut self) {
+        self.grid_locked.clear_all_filled_rows();
+        self.clearing_rows.clear();
+    }
+
+    /// Wait out the entry delay, or spawn the next piece right away if there isn't one. Initial rotation and hold
+    /// only apply after a delay, so keys still held from the last piece can't cause a misdrop.
+    fn start_spawning(&mut self) {
+        self.top_up_cheese();
+        self.apply_pending_garbage();
+
+        if self.is_game_over {
+            return;
+        }
+
+        if self.get_are_ticks() > 0 {
+            self.phase = Phase::Spawning;
+            self.ticks_to_phase_end = self.get_are_ticks();
+        } else if self.phase == Phase::LineClear {
+            self.spawn_next_piece();
+        } else {
+            self.increase_master_level_for_spawn();
+            self.next_piece();
+        }
+    }
+
+    /// Count down the line clear or entry delay, moving on to the next phase once it runs out.
+    fn step_delay(&mut self) {
+        self.ticks_to_phase_end -= 1;
+
+        if self.ticks_to_phase_end > 0 {
+            return;
+        }
+
+        match self.phase {
+            Phase::LineClear => {
+                self.collapse_cleared_rows();
+                self.start_spawning();
+            }
+            Phase::Spawning => self.spawn_next_piece(),
+            Phase::Falling | Phase::Locking => (),
+        }
+    }
+
+    /// Spawn the next piece once the entry delay is over, swapping it straight into the hold if hold was pressed
+    /// during the delays or is still held.
+    fn spawn_next_piece(&mut self) {
+        // The keys held as the piece spawns count, not just the ones pressed during the delays, so a key held
+        // since before the delays started still applies.
+        let input = self.last_input;
+
+        self.increase_master_level_for_spawn();
+        self.buffer_initial_actions(GameInput {
+            rotate_right: input.rotate_right_held,
+            rotate_left: input.rotate_left_held,
+            rotate_180: input.rotate_180_held,
+            hold_piece: input.hold_piece_held,
+            ..Default::default()
+        });
+
+        if !std::mem::take(&mut self.is_initial_hold) {
+            return self.next_piece();
+        }
+
+        let next_active_piece = match self.held_piece {
+            Some(held_piece) => {
+                self.held_piece = Some(self.randomizer.next());
+                held_piece
+            }
+            None => {
+                self.held_piece = Some(self.randomizer.next());
+                self.randomizer.next()
+            }
+        };
+
+        self.set_active_piece_and_reset_state(next_active_piece);
+        self.last_piece_swapped = true;
+    }
+
+    /// Remember rotation and hold inputs pressed while there's no active piece, if the mode allows it.
+    fn buffer_initial_actions(&mut self, input: GameInput) {
+        if !self.mode.get_has_initial_actions() {
+            return;
+        }
+
+        if input.rotate_right {
+            self.initial_rotation = Some(Rotation::Clockwise);
+        }
+
+        if input.rotate_left {
+            self.initial_rotation = Some(Rotation::CounterClockwise);
+        }
+
+        if input.rotate_180 {
+            self.initial_rotation = Some(Rotation::Half);
+        }
+
+        if input.hold_piece {
+            self.is_initial_hold = true;
+        }
+    }
+
+    fn is_piece_active(&self) -> bool {
+        matches!(self.phase, Phase::Falling | Phase::Locking)
+    }
+
+    fn hard_drop(&mut self) {
+        let landing_row = self.grid_locked.find_landing_row(
+            self.active_piece_row,
+            self.active_piece_col,
+            &self.cached_blocks,
+            self.cached_bounds_height,
+            self.cached_bounds_width,
+        );
+
+        let lines_dropped = (landing_row - self.active_piece_row).max(0);
+        self.active_piece_row = landing_row;
+
+        if lines_dropped > 0 {
+            self.last_rotation_kick = None;
+        }
+
+        self.score += 2 * lines_dropped as usize;
+
+        self.lock_active_piece_and_get_next();
+    }
+
+    /// Drop the active piece straight to the floor, without locking it. This is infinite soft drop.
+    fn sonic_drop(&mut self) {
+        let landing_row = self.grid_locked.find_landing_row(
+            self.active_piece_row,
+            self.active_piece_col,
+            &self.cached_blocks,
+            self.cached_bounds_height,
+            self.cached_bounds_width,
+        );
+
+        let lines_dropped = landing_row - self.active_piece_row;
+
+        if lines_dropped > 0 {
+            self.score += lines_dropped as usize;
+            self.set_active_piece_row_and_reset_ticks(landing_row);
+        }
+    }
+
+    fn get_rows_to_drop(&self) -> usize {
+        if self.ticks_to_next_row_inc > 0 {
+            return 0;
+        }
+
+        // Above 1G, the piece falls more than one row per tick.
+        (self.get_gravity().floor() as usize).max(1)
+    }
+
+    /// Move the active piece down by up to the given number of rows, checking for a collision at every row so
+    /// it can't pass through the stack. Returns the number of rows it actually moved.
+    fn drop_active_piece(&mut self, rows: usize) -> usize {
+        let mut next_active_piece_row = self.active_piece_row;
+
+        for _ in 0..rows {
+            if self.collide(Some(next_active_piece_row + 1), None, None) {
+                break;
+            }
+
+            next_active_piece_row += 1;
+        }
+
+        let rows_dropped = (next_active_piece_row - self.active_piece_row) as usize;
+
+        if rows_dropped > 0 {
+            self.set_active_piece_row_and_reset_ticks(next_active_piece_row);
+        }
+
+        rows_dropped
+    }
+
+    fn collide(
+        &self,
+        row_id: Option<isize>,
+        col_id: Option<isize>,
+        orientation: Option<usize>,
+    ) -> bool {
+        let orientation = orientation.unwrap_or(self.active_piece_orientation);
+
+        // Use cached blocks if orientation matches, otherwise compute fresh
+        if orientation == self.active_piece_orientation {
+            self.grid_locked.collision_check(
+                row_id.unwrap_or(self.active_piece_row),
+                col_id.unwrap_or(self.active_piece_col),
+                &self.cached_blocks,
+                self.cached_bounds_height,
+                self.cached_bounds_width,
+            )
+        } else {
+            let (blocks, height, width) = self.active_piece.get_blocks(orientation);
+            self.grid_locked.collision_check(
+                row_id.unwrap_or(self.active_piece_row),
+                col_id.unwrap_or(self.active_piece_col),
+                &blocks,
+                height,
+                width,
+            )
+        }
+    }
+
+    fn try_rotate(&mut self, rotation: Rotation) {
+        let next_orientation = rotation.apply(self.active_piece_orientation);
+        let (kick_tests, kick_test_count) = self
+            .active_piece
+            .get_kick_tests(self.active_piece_orientation, rotation);
+
+        for (kick_index, &(offset_col, offset_row)) in
+            kick_tests[..kick_test_count].iter().enumerate()
+        {
+            // I'm subtracting the offset from active_piece_row instead of adding it here,
+            // because rows are counted from the bottom in the Guideline. They're counted
+            // in the other direction in my Grid implementation, so the offsets I copied
+            // from the Guideline have to get applied backwards here.
+            let next_active_piece_row = self.active_piece_row - offset_row;
+            let next_active_piece_col = self.active_piece_col + offset_col;
+
+            let has_collision = self.collide(
+                Some(next_active_piece_row),
+                Some(next_active_piece_col),
+                Some(next_orientation),
+            );
+
+            if !has_collision {
+                self.active_piece_orientation = next_orientation;
+                self.active_piece_row = next_active_piece_row;
+                self.active_piece_col = next_active_piece_col;
+                self.refresh_cached_blocks();
+                self.try_reset_lock_delay_for_move();
+                self.last_rotation_kick = Some((rotation, kick_index));
+
+                // Hold off auto repeat for a moment, so a charged DAS doesn't immediately shift the rotated
+                // piece.
+                self.ticks_to_repeat = self.ticks_to_repeat.max(self.handling.das_cut_ticks);
+
+                return;
+            }
+        }
+    }
+
+    fn set_shift_direction_and_reset_ticks(&mut self, new_shift_direction: ShiftDirection) {
+        self.ticks_to_repeat = self.handling.das_ticks;
+        self.shift_direction = new_shift_direction;
+    }
+
+    fn try_move_horizontal(&mut self, is_shift_left: bool, is_shift_right: bool) {
+        let mut col_offset = 0;
+
+        if !is_shift_left && !is_shift_right {
+            return self.set_shift_direction_and_reset_ticks(ShiftDirection::Neither);
+        }
+
+        match self.shift_direction {
+            ShiftDirection::Left => {
+                if !is_shift_left {
+                    if is_shift_right {
+                        self.set_shift_direction_and_reset_ticks(ShiftDirection::Right);
+                        col_offset = 1;
+                    }
+                } else {
+                    self.ticks_to_repeat -= 1;
+                }
+            }
+
+            ShiftDirection::Right => {
+                if !is_shift_right {
+                    if is_shift_left {
+                        self.set_shift_direction_and_reset_ticks(ShiftDirection::Left);
+                        col_offset = -1;
+                    }
+                } else {
+                    self.ticks_to_repeat -= 1;
+                }
+            }
+
+            ShiftDirection::Neither => {
+                if is_shift_left {
+                    self.set_shift_direction_and_reset_ticks(ShiftDirection::Left);
+                    col_offset = -1;
+                } else if is_shift_right {
+                    self.set_shift_direction_and_reset_ticks(ShiftDirection::Right);
+                    col_offset = 1;
+                }
+            }
+        }
+
+        let mut shift_count = 1;
+
+        if self.ticks_to_repeat <= 0 {
+            col_offset = match self.shift_direction {
+                ShiftDirection::Left => -1,
+                ShiftDirection::Right => 1,
+                ShiftDirection::Neither => unreachable!(),
+            };
+
+            self.ticks_to_repeat = self.handling.arr_ticks;
+
+            // With an ARR of 0, a repeat shifts the piece as far as it can go.
+            if self.handling.arr_ticks == 0 {
+                shift_count = GRID_COUNT_COLS;
+            }
+        }
+
+        if col_offset == 0 {
+            return;
+        }
+
+        let mut has_moved = false;
+
+        for _ in 0..shift_count {
+            let next_active_piece_col = self.active_piece_col + col_offset;
+
+            // Horizontal collision check
+            if self.collide(None, Some(next_active_piece_col), None) {
+                break;
+            }
+
+            self.active_piece_col = next_active_piece_col;
+            has_moved = true;
+        }
+
+        if has_moved {
+            self.piece_dirty = true;
+            self.try_reset_lock_delay_for_move();
+            self.last_rotation_kick = None;
+        }
+    }
+
+    fn set_active_piece_row_and_reset_ticks(&mut self, new_active_piece_row: isize) {
+        self.active_piece_row = new_active_piece_row;
+        self.ticks_to_next_row_inc = self.get_new_ticks_to_next_row_inc();
+        self.last_rotation_kick = None;
+        self.piece_dirty = true;
+
+        // Falling back down to a row the piece has already reached (e.g. after kicking upward) doesn't refill the
+        // move budget or the lock delay, as in the Guideline.
+        if new_active_piece_row > self.lowest_row_reached {
+            self.lowest_row_reached = new_active_piece_row;
+            self.lock_reset_moves_remaining = self.handling.reset_moves;
+            self.ticks_to_lock = self.get_lock_delay_ticks();
+        }
+    }
+
+    fn try_gravity_drop(&mut self, is_soft_drop: bool) {
+        if self.is_game_over {
+            return;
+        }
+
+        // The lock delay counts down on every tick the piece is resting on something, not only when gravity
+        // tries to move it. Otherwise, kicking upward just before each gravity drop would keep it from ever
+        // counting down.
+        if self.collide(Some(self.active_piece_row + 1), None, None) {
+            self.phase = Phase::Locking;
+            self.ticks_to_lock -= 1;
+
+            if self.ticks_to_lock <= 0 {
+                self.lock_active_piece_and_get_next();
+            }
+
+            return;
+        }
+
+        self.phase = Phase::Falling;
+
+        let rows_dropped = self.drop_active_piece(self.get_rows_to_drop());
+
+        if is_soft_drop {
+            self.score += rows_dropped;
+        }
+    }
+
+    /// Handle the input for a tick where there's an active piece.
+    fn step_active_piece(&mut self, input: GameInput) {
+        let speed_modifier = match (input.soft_drop, self.handling.soft_drop) {
+            (true, SoftDrop::Fixed) => (FIXED_SOFT_DROP_GRAVITY / self.get_gravity())
+                .ceil()
+                .max(1.0) as usize,
+            (true, SoftDrop::Factor(factor)) => factor.max(1),
+            _ => 1,
+        };
+
+        self.ticks_to_next_row_inc -= speed_modifier as isize;
+
+        if input.hold_piece {
+            self.swap_active_piece();
+        }
+
+        // Count presses for finesse. Each rotation is a press, but a shift only counts on the tick it's pressed.
+        let (was_shift_left, was_shift_right) = self.last_shift_inputs;
+        self.finesse_inputs += [
+            input.shift_left && !was_shift_left,
+            input.shift_right && !was_shift_right,
+            input.rotate_right,
+            input.rotate_left,
+            input.rotate_180,
+        ]
+        .iter()
+        .filter(|&&is_press| is_press)
+        .count();
+        self.last_shift_inputs = (input.shift_left, input.shift_right);
+
+        if input.rotate_right {
+            self.try_rotate(Rotation::Clockwise);
+        }
+
+        if input.rotate_left {
+            self.try_rotate(Rotation::CounterClockwise);
+        }
+
+        if input.rotate_180 {
+            self.try_rotate(Rotation::Half);
+        }
+
+        if input.hard_drop {
+            self.hard_drop();
+
+            // The next piece doesn't spawn until after the delays, if there are any.
+            if self.is_game_over || !self.is_piece_active() {
+                return;
+            }
+        }
+
+        // Try and move the piece horizontally,
+        self.try_move_horizontal(input.shift_left, input.shift_right);
+
+        if input.soft_drop && self.handling.soft_drop == SoftDrop::Infinite {
+            self.sonic_drop();
+        }
+
+        // Drop the piece, or lock it if dropping would cause a collision.
+        self.try_gravity_drop(input.soft_drop);
+    }
+
+    /// Advance the simulation by exactly one tick, using the given input. Nothing here depends on wall-clock
+    /// time, so the same sequence of inputs always produces the same game.
+    pub fn step(&mut self, input: GameInput) {
+        if input.toggle_pause {
+            self.toggle_pause();
+        }
+
+        if self.is_game_over || self.is_paused {
+            return;
+        }
+
+        self.tick += 1;
+        Arc::make_mut(&mut self.recording).inputs.push(GameInput {
+            toggle_pause: false,
+            ..input
+        });
+        self.stats.add_keys_pressed(input, self.last_input);
+        self.last_input = input;
+
+        if self.is_piece_active() {
+            self.step_active_piece(input);
+        } else {
+            self.buffer_initial_actions(input);
+            self.step_delay();
+        }
+
+        // Only update grids if piece state changed
+        if self.piece_dirty && !self.is_piece_active() {
+            self.grid_active.clear();
+            self.grid_ghost.clear();
+            self.piece_dirty = false;
+        } else if self.piece_dirty {
+            self.grid_active.clear().set_cells(
+                self.active_piece_row,
+                self.active_piece_col,
+                &self.cached_blocks,
+                self.cached_bounds_height,
+                self.cached_bounds_width,
+            );
+
+            self.cached_ghost_row = self.grid_locked.find_landing_row(
+                self.active_piece_row,
+                self.active_piece_col,
+                &self.cached_blocks,
+                self.cached_bounds_height,
+                self.cached_bounds_width,
+            );
+
+            self.grid_ghost.clear().set_cells(
+                self.cached_ghost_row,
+                self.active_piece_col,
+                &self.cached_blocks,
+                self.cached_bounds_height,
+                self.cached_bounds_width,
+            );
+
+            self.piece_dirty = false;
+        }
+
+        if !self.is_game_over {
+            self.check_for_goal();
+        }
+
+        // Record a checkpoint at regular intervals, and when the game ends.
+        if self.is_game_over || self.tick.is_multiple_of(CHECKPOINT_INTERVAL_TICKS) {
+            let checkpoint = (self.tick, self.get_state_hash());
+            Arc::make_mut(&mut self.recording)
+                .checkpoints
+                .push(checkpoint);
+        }
+    }
+
+    /// Get a hash of everything that affects how the game plays out: the stack, the active and held pieces, and
+    /// the score. Two games that have been fed the same inputs should always have the same hash.
+    pub fn get_state_hash(&self) -> u64 {
+        // FNV-1a, which is stable across platforms and versions, unlike std's DefaultHasher.
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut write = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+
+        for row_id in 0..GRID_COUNT_ROWS {
+            let mut row_bits: u64 = 0;
+
+            for col_id in 0..GRID_COUNT_COLS {
+                if self.grid_locked.has_block_at_cell(row_id, col_id) {
+                    row_bits |= 1 << col_id;
+                }
+            }
+
+            write(row_bits);
+        }
+
+        write(self.active_piece.name.as_bytes()[0] as u64);
+        write(self.active_piece_row as u64);
+        write(self.active_piece_col as u64);
+        write(self.active_piece_orientation as u64);
+        write(self.held_piece.map_or(0, |piece| piece.name.as_bytes()[0]) as u64);
+        write(self.score as u64);
+        write(self.rows_cleared as u64);
+        write(self.master_level as u64);
+        write(self.get_pending_garbage() as u64);
+        write(self.garbage_rows_cleared as u64);
+        write(self.phase as u64);
+        write(self.ticks_to_phase_end as u64);
+        write(
+            self.initial_rotation
+                .map_or(0, |rotation| rotation as u64 + 1),
+        );
+        write(self.is_initial_hold as u64);
+
+        hash
+    }
+
+    /// Copy the game to try inputs out on. The copy records its own replay from here, rather than copying this
+    /// game's.
+    pub fn clone_without_replay(&self) -> Self {
+        Self {
+            recording: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Get a replay of the game so far, which can be played back by feeding its inputs into a fresh game.
+    pub fn get_replay(&self) -> Replay {
+        Replay {
+            rules_version: RULES_VERSION,
+            mode: self.mode,
+            randomizer_kind: self.randomizer_kind,
+            seed: self.seed,
+            handling: self.handling,
+            score: self.score,
+            rows_cleared: self.rows_cleared,
+            is_game_over: self.is_game_over,
+            inputs: self.recording.inputs.clone(),
+            checkpoints: self.recording.checkpoints.clone(),
+        }
+    }
+
+    fn increase_rows_cleared(&mut self, new_rows_cleared: usize) {
+        self.rows_cleared += new_rows_cleared;
+    }
+
+    /// Score the rows filled by the piece that just locked. The rows stay on the stack until the line clear
+    /// delay is over.
+    fn update_score_for_filled_rows(&mut self, spin: SpinKind) {
+        self.clearing_rows = self.grid_locked.get_filled_rows();
+        self.garbage_rows_cleared += self
+            .clearing_rows
+            .iter()
+            .filter(|&&row_id| self.grid_locked.is_garbage_row(row_id))
+            .count();
+
+        let rows_cleared = self.clearing_rows.len();
+        let level = self.get_level();
+        let clear_kind = ClearKind::from_rows_cleared(rows_cleared, spin);
+        let is_perfect_clear = rows_cleared > 0 && self.grid_locked.is_empty_after_clear();
+
+        if is_perfect_clear {
+            let is_back_to_back_tetris = self.back_to_back && clear_kind == Some(ClearKind::Tetris);
+            self.score += get_perfect_clear_points(rows_cleared, is_back_to_back_tetris) * level;
+            self.stats.perfect_clears += 1;
+        }
+
+        let is_back_to_back_bonus =
+            self.back_to_back && clear_kind.is_some_and(|clear_kind| clear_kind.is_difficult());
+
+        if let Some(clear_kind) = clear_kind {
+            match clear_kind {
+                ClearKind::Single => self.stats.singles += 1,
+                ClearKind::Double => self.stats.doubles += 1,
+                ClearKind::Triple => self.stats.triples += 1,
+                ClearKind::Tetris => self.stats.tetrises += 1,
+                ClearKind::TSpinMini(_) | ClearKind::TSpin(_) => self.stats.t_spins += 1,
+            }
+
+            let points = clear_kind.get_points() * level;
+
+            // Back-to-back difficult clears are worth 1.5x.
+            if is_back_to_back_bonus {
+                self.score += points * 3 / 2;
+            } else {
+                self.score += points;
+            }
+
+            // Only line clears affect the back-to-back chain; a T-spin that doesn't clear rows neither
+            // continues nor breaks it.
+            if rows_cleared > 0 {
+                self.back_to_back = clear_kind.is_difficult();
+            }
+        }
+
+        if rows_cleared > 0 {
+            self.combo += 1;
+            self.stats.max_combo = self.stats.max_combo.max(self.combo as usize);
+            self.score += 50 * self.combo as usize * level;
+        } else {
+            self.combo = -1;
+        }
+
+        if let (Some(clear_kind), true) = (clear_kind, rows_cleared > 0) {
+            let attack = self.attack_table.get_attack(
+                clear_kind,
+                is_back_to_back_bonus,
+                self.combo as usize,
+                is_perfect_clear,
+            );
+
+            self.stats.attack_sent += attack;
+            self.send_attack(attack);
+        }
+
+        self.last_clear_kind = clear_kind;
+        self.last_clear_was_perfect = is_perfect_clear;
+        self.increase_rows_cleared(rows_cleared);
+    }
+
+    pub fn get_grid_locked(&self) -> &Grid {
+        &self.grid_locked
+    }
+
+    pub fn get_grid_active(&self) -> &Grid {
+        &self.grid_active
+    }
+
+    pub fn get_grid_ghost(&self) -> &Grid {
+        &self.grid_ghost
+    }
+
+    /// Get the TGM-style level, in Master mode.
+    pub fn get_master_level(&self) -> Option<usize> {
+        match self.mode {
+            GameMode::Master => Some(self.master_level),
+            _ => None,
+        }
+    }
+
+    /// Get the number of garbage rows left to clear, in a Cheese race.
+    pub fn get_cheese_remaining(&self) -> Option<usize> {
+        match self.mode {
+            GameMode::Cheese(total_rows) => {
+                Some(total_rows.saturating_sub(self.garbage_rows_cleared))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the total number of garbage rows waiting to be added to the stack.
+    pub fn get_pending_garbage(&self) -> usize {
+        self.pending_garbage.iter().map(|(rows, _)| rows).sum()
+    }
+
+    pub fn get_phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Get the rows that are about to be cleared, while the line clear delay is running.
+    pub fn get_clearing_rows(&self) -> &[usize] {
+        &self.clearing_rows
+    }
+
+    /// Get how far through the line clear delay the game is, from 0 to 1, for animating the cleared rows.
+    pub fn get_line_clear_progress(&self) -> f32 {
+        if self.phase != Phase::LineClear {
+            return 0.0;
+        }
+
+        let delay = self.get_line_clear_delay_ticks() as f32;
+
+        (delay - self.ticks_to_phase_end as f32) / delay
+    }
+
+    pub fn get_score(&self) -> usize {
+        self.score
+    }
+
+    pub fn get_mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn get_tick(&self) -> usize {
+        self.tick
+    }
+
+    /// Get the time left before the game ends, in milliseconds, for modes that have a time limit.
+    pub fn get_remaining_ms(&self) -> Option<u64> {
+        match self.mode {
+            GameMode::Ultra => {
+                let remaining_ticks = ULTRA_TICKS.saturating_sub(self.tick);
+                Some((remaining_ticks as f64 * 1000.0 / TICKS_PER_SECOND as f64).round() as u64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the time spent playing (not paused) so far, in milliseconds, based on the number of ticks elapsed.
+    pub fn get_elapsed_ms(&self) -> u64 {
+        (self.tick as f64 * 1000.0 / TICKS_PER_SECOND as f64).round() as u64
+    }
+
+    pub fn get_rows_cleared(&self) -> usize {
+        self.rows_cleared
+    }
+
+    pub fn get_level(&self) -> usize {
+        // Minimum level is 1. Maximum is 20.
+        (self.rows_cleared as f32 / 10.0).ceil().clamp(1.0, 20.0) as usize
+    }
+
+    pub fn get_gravity(&self) -> f32 {
+        if self.mode == GameMode::Master {
+            return get_master_gravity(self.master_level);
+        }
+
+        let level = self.get_level();
+        let gravity_seconds = (0.8 - ((level - 1) as f32 * 0.007)).powi(level as i32 - 1);
+        let gravity_ticks_per_row = 1.0 / gravity_seconds;
+        let gravity = gravity_ticks_per_row / 60.0;
+
+        // Cap at 1G, or 1 row per tick.
+        gravity.min(1.0)
+    }
+
+    pub fn get_piece_previews(&self) -> [Piece; 3] {
+        [
+            self.randomizer.peek(1),
+            self.randomizer.peek(2),
+            self.randomizer.peek(3),
+        ]
+    }
+
+    pub fn get_active_piece(&self) -> Piece {
+        self.active_piece
+    }
+
+    pub fn get_active_piece_position(&self) -> PiecePosition {
+        PiecePosition {
+            row: self.active_piece_row,
+            col: self.active_piece_col,
+            orientation: self.active_piece_orientation,
+        }
+    }
+
+    /// Whether there's an active piece that can be moved, i.e. the game isn't waiting out a delay.
+    pub fn get_is_piece_active(&self) -> bool {
+        self.is_piece_active()
+    }
+
+    pub fn get_held_piece(&self) -> Option<Piece> {
+        self.held_piece
+    }
+
+    /// Whether the active piece can be swapped with the held piece. Only one swap is allowed per piece.
+    pub fn get_is_hold_available(&self) -> bool {
+        !self.last_piece_swapped
+    }
+
+    /// Get the kind of clear made by the last locked piece, if any.
+    pub fn get_last_clear_kind(&self) -> Option<ClearKind> {
+        self.last_clear_kind
+    }
+
+    /// Whether a back-to-back chain is active, i.e. the last line clear was a Tetris or T-spin.
+    pub fn get_is_back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+
+    /// Get the current combo count. This is 0 until at least two consecutive locks have cleared rows.
+    pub fn get_combo(&self) -> usize {
+        self.combo.max(0) as usize
+    }
+
+    /// Whether the last locked piece cleared every block from the playfield.
+    pub fn get_last_clear_was_perfect(&self) -> bool {
+        self.last_clear_was_perfect
+    }
+
+    pub fn get_perfect_clears(&self) -> usize {
+        self.stats.perfect_clears
+    }
+
+    pub fn get_stats(&self) -> GameStats {
+        self.stats
+    }
+
+    /// Get the placement the finesse trainer wants for the active piece, if this is the trainer.
+    pub fn get_finesse_target(&self) -> Option<PiecePosition> {
+        self.finesse_target
+    }
+
+    /// Get how the last locked piece was judged for finesse. None if it wasn't judged, e.g. it was tucked.
+    pub fn get_last_finesse_result(&self) -> Option<FinesseResult> {
+        self.last_finesse_result
+    }
+
+    pub fn get_finesse_pieces_by_piece(&self) -> [usize; 7] {
+        self.finesse_pieces_by_piece
+    }
+
+    pub fn get_finesse_faults_by_piece(&self) -> [usize; 7] {
+        self.finesse_faults_by_piece
+    }
+
+    pub fn get_finesse_pieces(&self) -> usize {
+        self.finesse_pieces_by_piece.iter().sum()
+    }
+
+    pub fn get_finesse_faults(&self) -> usize {
+        self.finesse_faults_by_piece.iter().sum()
+    }
+
+    /// Get the percentage of judged pieces that were placed without a finesse fault. None if nothing's been
+    /// judged yet.
+    pub fn get_finesse_percentage(&self) -> Option<f32> {
+        let pieces = self.get_finesse_pieces();
+
+        (pieces > 0).then(|| (pieces - self.get_finesse_faults()) as f32 / pieces as f32 * 100.0)
+    }
+
+    pub fn get_randomizer_kind(&self) -> RandomizerKind {
+        self.randomizer_kind
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_is_game_over(&self) -> bool {
+        self.is_game_over
+    }
+
+    pub fn get_is_goal_reached(&self) -> bool {
+        self.is_goal_reached
+    }
+
+    pub fn get_is_paused(&self) -> bool {
+        self.is_paused
+    }
+}
@@ -1,5 +1,6 @@
-use crate::block::Block;
+use crate::block::{Block, GARBAGE_COLOR};
 use crate::piece::BlockCanvas;
+use std::fmt::Display;
 
 pub const VISIBLE_GRID_COUNT_ROWS: usize = 20;
 pub const GRID_COUNT_ROWS: usize = 22;
@@ -46,9 +47,8 @@ impl Grid {
         bounds_height: usize,
         bounds_width: usize,
     ) -> &mut Self {
-        for canvas_row_id in 0..bounds_height {
-            for canvas_col_id in 0..bounds_width {
-                let canvas_cell = canvas[canvas_row_id][canvas_col_id];
+        for (canvas_row_id, canvas_row) in canvas.iter().enumerate().take(bounds_height) {
+            for (canvas_col_id, &canvas_cell) in canvas_row.iter().enumerate().take(bounds_width) {
                 if canvas_cell.is_some() {
                     let grid_col_id = canvas_col_id as isize + col_offset;
                     let grid_row_id = canvas_row_id as isize + row_offset;
@@ -80,9 +80,9 @@ impl Grid {
         bounds_height: usize,
         bounds_width: usize,
     ) -> bool {
-        for canvas_row_id in 0..bounds_height {
-            for canvas_col_id in 0..bounds_width {
-                if canvas[canvas_row_id][canvas_col_id].is_some() {
+        for (canvas_row_id, canvas_row) in canvas.iter().enumerate().take(bounds_height) {
+            for (canvas_col_id, canvas_cell) in canvas_row.iter().enumerate().take(bounds_width) {
+                if canvas_cell.is_some() {
                     let grid_col_id = canvas_col_id as isize + col_offset;
                     let grid_row_id = canvas_row_id as isize + row_offset;
 
@@ -110,9 +110,9 @@ impl Grid {
         bounds_height: usize,
         bounds_width: usize,
     ) -> bool {
-        for canvas_row_id in 0..bounds_height {
-            for canvas_col_id in 0..bounds_width {
-                if canvas[canvas_row_id][canvas_col_id].is_some() {
+        for (canvas_row_id, canvas_row) in canvas.iter().enumerate().take(bounds_height) {
+            for canvas_cell in canvas_row.iter().take(bounds_width) {
+                if canvas_cell.is_some() {
                     let grid_row_id = canvas_row_id as isize + row_offset;
 
                     // If any row ID would be at or below the first visible row, then this canvas would not be
@@ -136,8 +136,13 @@ impl Grid {
         bounds_width: usize,
     ) -> isize {
         for next_row_offset in row_offset..GRID_COUNT_ROWS as isize {
-            let has_collision =
-                self.collision_check(next_row_offset, col_offset, canvas, bounds_height, bounds_width);
+            let has_collision = self.collision_check(
+                next_row_offset,
+                col_offset,
+                canvas,
+                bounds_height,
+                bounds_width,
+            );
 
             if has_collision {
                 return next_row_offset - 1;
@@ -163,6 +168,19 @@ impl Grid {
         self.rows[row_id][col_id].is_some()
     }
 
+    /// Like has_block_at_cell, but treats cells outside of the grid (walls, floor, and ceiling) as occupied.
+    pub fn is_cell_occupied(&self, row_id: isize, col_id: isize) -> bool {
+        if row_id < 0
+            || col_id < 0
+            || row_id >= GRID_COUNT_ROWS as isize
+            || col_id >= GRID_COUNT_COLS as isize
+        {
+            return true;
+        }
+
+        self.has_block_at_cell(row_id as usize, col_id as usize)
+    }
+
     pub fn clear_row(&mut self, row_id: usize) -> &mut Self {
         if row_id >= GRID_COUNT_ROWS {
             return self;
@@ -188,6 +206,50 @@ impl Grid {
         true
     }
 
+    pub fn is_row_empty(&self, row_id: usize) -> bool {
+        (0..GRID_COUNT_COLS).all(|col_id| !self.has_block_at_cell(row_id, col_id))
+    }
+
+    pub fn get_filled_rows(&self) -> Vec<usize> {
+        (0..GRID_COUNT_ROWS)
+            .filter(|&row_id| self.is_row_filled(row_id))
+            .collect()
+    }
+
+    /// Check if the given row has any garbage in it.
+    pub fn is_garbage_row(&self, row_id: usize) -> bool {
+        (0..GRID_COUNT_COLS).any(|col_id| {
+            self.get_cell(row_id, col_id)
+                .is_some_and(|block| block.color == GARBAGE_COLOR)
+        })
+    }
+
+    /// Push the whole grid up by the given number of rows, and fill the rows that open up at the bottom with
+    /// garbage that has a hole in the given column. Returns true if any blocks were pushed off the top.
+    pub fn insert_garbage(&mut self, count: usize, hole_col: usize) -> bool {
+        let count = count.min(GRID_COUNT_ROWS);
+        let is_overflow = (0..count).any(|row_id| !self.is_row_empty(row_id));
+
+        self.rows.rotate_left(count);
+
+        for row_id in (GRID_COUNT_ROWS - count)..GRID_COUNT_ROWS {
+            for col_id in 0..GRID_COUNT_COLS {
+                self.rows[row_id][col_id] = if col_id == hole_col {
+                    None
+                } else {
+                    Some(Block::new(GARBAGE_COLOR))
+                };
+            }
+        }
+
+        is_overflow
+    }
+
+    /// Check if there would be no blocks left anywhere in the grid once its filled rows are cleared.
+    pub fn is_empty_after_clear(&self) -> bool {
+        (0..GRID_COUNT_ROWS).all(|row_id| self.is_row_filled(row_id) || self.is_row_empty(row_id))
+    }
+
     // Clears all filled rows (if any), returning the number of rows cleared.
     pub fn clear_all_filled_rows(&mut self) -> usize {
         let mut cleared_row_ids: Vec<usize> = Vec::new();
@@ -216,3 +278,30 @@ impl Grid {
         cleared_row_count
     }
 }
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws the visible rows of the grid as text, one line per row, with `#` for blocks and `.` for empty cells.
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row_id in FIRST_VISIBLE_ROW_ID..GRID_COUNT_ROWS {
+            let line: String = (0..GRID_COUNT_COLS)
+                .map(|col_id| {
+                    if self.has_block_at_cell(row_id, col_id) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use crate::storage::{read_records, write_records};
+use std::collections::HashMap;
+
+const HANDLING_PATH: &str = "./.handling";
+
+/// The fastest soft drop factor before infinite.
+const MAX_SOFT_DROP_FACTOR: usize = 40;
+
+/// How fast soft drop moves the piece down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoftDrop {
+    /// 1/2G at every level, or gravity itself once that's faster.
+    Fixed,
+    /// Some number of times faster than gravity.
+    Factor(usize),
+    /// Drops straight to the floor without locking (sonic drop).
+    Infinite,
+}
+
+/// The speed of fixed soft drop, in rows per tick. 30 rows per 60 ticks (1 second).
+pub const FIXED_SOFT_DROP_GRAVITY: f32 = 30.0 / 60.0;
+
+/// Per-player handling preferences. All durations are in ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HandlingConfig {
+    /// Delayed auto shift. How long a shift has to be held before it starts repeating.
+    pub das_ticks: isize,
+    /// Auto repeat rate. The interval between repeated shifts. 0 shifts all the way to the wall instantly.
+    pub arr_ticks: isize,
+    pub soft_drop: SoftDrop,
+    /// DAS cut delay. How long auto repeat is held off after a rotation, so a charged DAS doesn't immediately
+    /// shift the rotated piece.
+    pub das_cut_ticks: isize,
+    /// How long a piece can rest on the stack before it locks.
+    pub lock_delay_ticks: isize,
+    /// Number of shifts or rotations allowed before lock delay can no longer be reset.
+    pub reset_moves: isize,
+    /// Entry delay. How long the next piece takes to appear after the previous one locks.
+    pub are_ticks: isize,
+    /// How long cleared rows stay on the stack before the rows above fall into their place.
+    pub line_clear_delay_ticks: isize,
+}
+
+impl Default for HandlingConfig {
+    fn default() -> Self {
+        Self {
+            das_ticks: 11, // ~183ms
+            arr_ticks: 4,  // ~67ms, or 15 times per second
+            soft_drop: SoftDrop::Fixed,
+            das_cut_ticks: 0,     // Off
+            lock_delay_ticks: 30, // 500ms
+            reset_moves: 15,
+            are_ticks: 0,              // Off
+            line_clear_delay_ticks: 0, // Off
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandlingSetting {
+    Das,
+    Arr,
+    SoftDropFactor,
+    DasCut,
+    LockDelay,
+    ResetMoves,
+    Are,
+    LineClearDelay,
+}
+
+impl HandlingSetting {
+    pub const ALL: [HandlingSetting; 8] = [
+        HandlingSetting::Das,
+        HandlingSetting::Arr,
+        HandlingSetting::SoftDropFactor,
+        HandlingSetting::DasCut,
+        HandlingSetting::LockDelay,
+        HandlingSetting::ResetMoves,
+        HandlingSetting::Are,
+        HandlingSetting::LineClearDelay,
+    ];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            HandlingSetting::Das => "DAS",
+            HandlingSetting::Arr => "ARR",
+            HandlingSetting::SoftDropFactor => "SDF",
+            HandlingSetting::DasCut => "DCD",
+            HandlingSetting::LockDelay => "Lock",
+            HandlingSetting::ResetMoves => "Resets",
+            HandlingSetting::Are => "ARE",
+            HandlingSetting::LineClearDelay => "Clear",
+        }
+    }
+
+    fn get_id(&self) -> &'static str {
+        match self {
+            HandlingSetting::Das => "das",
+            HandlingSetting::Arr => "arr",
+            HandlingSetting::SoftDropFactor => "sdf",
+            HandlingSetting::DasCut => "dcd",
+            HandlingSetting::LockDelay => "lock_delay",
+            HandlingSetting::ResetMoves => "reset_moves",
+            HandlingSetting::Are => "are",
+            HandlingSetting::LineClearDelay => "line_clear_delay",
+        }
+    }
+}
+
+impl HandlingConfig {
+    /// Load the saved handling config, falling back to the defaults for anything that hasn't been saved.
+    pub fn load() -> Self {
+        let records = read_records(HANDLING_PATH);
+        let mut config = Self::default();
+
+        for setting in HandlingSetting::ALL {
+            if let Some(&value) = records.get(setting.get_id()) {
+                config.set_raw(setting, value);
+            }
+        }
+
+        config
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let records: HashMap<String, u64> = HandlingSetting::ALL
+            .iter()
+            .map(|setting| (setting.get_id().to_string(), self.get_raw(*setting)))
+            .collect();
+
+        write_records(HANDLING_PATH, &records)
+    }
+
+    /// Get the raw value of the given setting, for storage. Soft drop is stored as 0 for fixed, then its factor,
+    /// then one past the highest factor for infinite.
+    pub fn get_raw(&self, setting: HandlingSetting) -> u64 {
+        match setting {
+            HandlingSetting::Das => self.das_ticks as u64,
+            HandlingSetting::Arr => self.arr_ticks as u64,
+            HandlingSetting::SoftDropFactor => match self.soft_drop {
+                SoftDrop::Fixed => 0,
+                SoftDrop::Factor(factor) => factor as u64,
+                SoftDrop::Infinite => MAX_SOFT_DROP_FACTOR as u64 + 1,
+            },
+            HandlingSetting::DasCut => self.das_cut_ticks as u64,
+            HandlingSetting::LockDelay => self.lock_delay_ticks as u64,
+            HandlingSetting::ResetMoves => self.reset_moves as u64,
+            HandlingSetting::Are => self.are_ticks as u64,
+            HandlingSetting::LineClearDelay => self.line_clear_delay_ticks as u64,
+        }
+    }
+
+    pub fn set_raw(&mut self, setting: HandlingSetting, value: u64) {
+        match setting {
+            HandlingSetting::Das => self.das_ticks = value as isize,
+            HandlingSetting::Arr => self.arr_ticks = value as isize,
+            HandlingSetting::SoftDropFactor => {
+                self.soft_drop = match value as usize {
+                    0 => SoftDrop::Fixed,
+                    factor if factor <= MAX_SOFT_DROP_FACTOR => SoftDrop::Factor(factor),
+                    _ => SoftDrop::Infinite,
+                }
+            }
+            HandlingSetting::DasCut => self.das_cut_ticks = value as isize,
+            HandlingSetting::LockDelay => self.lock_delay_ticks = value.max(1) as isize,
+            HandlingSetting::ResetMoves => self.reset_moves = value as isize,
+            HandlingSetting::Are => self.are_ticks = value as isize,
+            HandlingSetting::LineClearDelay => self.line_clear_delay_ticks = value as isize,
+        }
+    }
+
+    /// Get the value of the given setting, formatted for display.
+    pub fn get_display_value(&self, setting: HandlingSetting) -> String {
+        match setting {
+            HandlingSetting::SoftDropFactor => match self.soft_drop {
+                SoftDrop::Fixed => String::from("1/2G"),
+                SoftDrop::Factor(factor) => format!("{}x", factor),
+                SoftDrop::Infinite => String::from("Inf"),
+            },
+            HandlingSetting::ResetMoves => self.reset_moves.to_string(),
+            _ => format!("{}f", self.get_raw(setting)),
+        }
+    }
+
+    /// Step the given setting up or down by one. Soft drop goes from fixed, to 1x up to 40x, then to infinite.
+    pub fn adjust(&mut self, setting: HandlingSetting, increase: bool) {
+        let (min, max) = match setting {
+            HandlingSetting::Das => (0, 30),
+            HandlingSetting::Arr => (0, 10),
+            HandlingSetting::SoftDropFactor => (0, MAX_SOFT_DROP_FACTOR as u64 + 1),
+            HandlingSetting::DasCut => (0, 20),
+            HandlingSetting::LockDelay => (1, 120),
+            HandlingSetting::ResetMoves => (0, 30),
+            HandlingSetting::Are => (0, 60),
+            HandlingSetting::LineClearDelay => (0, 60),
+        };
+
+        let value = self.get_raw(setting);
+
+        let next_value = if increase {
+            (value + 1).min(max)
+        } else {
+            value.saturating_sub(1).max(min)
+        };
+
+        self.set_raw(setting, next_value);
+    }
+}
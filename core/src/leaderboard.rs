@@ -0,0 +1,241 @@
+//! The best results in each mode, kept as a top 10 per mode in a JSON file in the platform's data directory.
+
+use crate::game_mode::GameMode;
+use crate::game_state::GameState;
+use crate::storage::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of entries kept for each mode.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+const LEADERBOARD_FILE_NAME: &str = "leaderboard.json";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io(std::io::Error),
+    Invalid(String),
+}
+
+impl Display for LeaderboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeaderboardError::Io(error) => write!(f, "{}", error),
+            LeaderboardError::Invalid(reason) => write!(f, "Invalid leaderboard: {}", reason),
+        }
+    }
+}
+
+impl From<std::io::Error> for LeaderboardError {
+    fn from(error: std::io::Error) -> Self {
+        LeaderboardError::Io(error)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    /// The score, or the finesse percentage (rounded down) in the finesse trainer
+    pub score: u64,
+    pub time_ms: u64,
+    pub lines: u64,
+    /// The level reached, which in Master is the internal level out of 999
+    pub level: u64,
+    /// When the game was played, in seconds since the Unix epoch. None for results carried over from the old
+    /// high score file.
+    pub date: Option<u64>,
+    /// None for results carried over from the old high score file
+    pub seed: Option<u64>,
+}
+
+impl LeaderboardEntry {
+    /// Get the entry for a finished game, if its result counts in its mode. Games that end early only count in
+    /// the modes that are played until topping out.
+    pub fn from_game(game_state: &GameState, name: &str) -> Option<Self> {
+        let score = match game_state.get_mode() {
+            GameMode::Marathon | GameMode::Master => game_state.get_score() as u64,
+            GameMode::Ultra | GameMode::Sprint | GameMode::Cheese(_) => {
+                if !game_state.get_is_goal_reached() {
+                    return None;
+                }
+
+                game_state.get_score() as u64
+            }
+            GameMode::Finesse => {
+                if !game_state.get_is_goal_reached() {
+                    return None;
+                }
+
+                game_state.get_finesse_percentage()? as u64
+            }
+        };
+
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .ok();
+
+        Some(Self {
+            name: name.to_string(),
+            score,
+            time_ms: game_state.get_elapsed_ms(),
+            lines: game_state.get_rows_cleared() as u64,
+            level: game_state
+                .get_master_level()
+                .unwrap_or(game_state.get_level()) as u64,
+            date,
+            seed: Some(game_state.get_seed()),
+        })
+    }
+
+    /// Whether this entry ranks ahead of another in the given mode. Timed modes rank the fastest first, and the
+    /// rest rank the highest score first.
+    pub fn get_is_better_than(&self, other: &Self, mode: GameMode) -> bool {
+        if mode.get_is_timed() {
+            self.time_ms < other.time_ms
+        } else {
+            self.score > other.score
+        }
+    }
+}
+
+/// Format a date in seconds since the Unix epoch as "YYYY-MM-DD", in UTC.
+pub fn format_date(timestamp: u64) -> String {
+    // Converts days since the epoch into a civil date, treating years as starting in March so the leap day
+    // falls at the end. From Howard Hinnant's date algorithms.
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Leaderboard {
+    version: u32,
+    // Entries for each mode, keyed by mode ID, best first
+    #[serde(default)]
+    modes: BTreeMap<String, Vec<LeaderboardEntry>>,
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            modes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Leaderboard {
+    /// Get where the leaderboard is stored: in a "bloxide" folder in the platform's data directory, or the
+    /// working directory if there isn't one.
+    pub fn get_path() -> PathBuf {
+        dirs::data_dir()
+            .map_or_else(|| PathBuf::from("."), |dir| dir.join("bloxide"))
+            .join(LEADERBOARD_FILE_NAME)
+    }
+
+    pub fn load() -> Result<Self, LeaderboardError> {
+        Self::load_from(&Self::get_path())
+    }
+
+    /// Load a leaderboard from the given path. A missing file is an empty leaderboard.
+    pub fn load_from(path: &Path) -> Result<Self, LeaderboardError> {
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let leaderboard: Self = serde_json::from_str(&data)
+            .map_err(|error| LeaderboardError::Invalid(error.to_string()))?;
+
+        if leaderboard.version > FORMAT_VERSION {
+            return Err(LeaderboardError::Invalid(format!(
+                "unsupported version {}",
+                leaderboard.version
+            )));
+        }
+
+        Ok(leaderboard)
+    }
+
+    /// Move an unreadable leaderboard file aside, so saving a new leaderboard doesn't overwrite the records in
+    /// it. Returns where it was moved to.
+    pub fn back_up(path: &Path) -> Result<PathBuf, LeaderboardError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        let mut backup_path = path.as_os_str().to_owned();
+        backup_path.push(format!(".{}.bak", timestamp));
+
+        fs::rename(path, &backup_path)?;
+        Ok(PathBuf::from(backup_path))
+    }
+
+    pub fn save(&self) -> Result<(), LeaderboardError> {
+        self.save_to(&Self::get_path())
+    }
+
+    /// Save the leaderboard to the given path, creating its directory if needed. The file is replaced in one
+    /// go, so it's never left half written.
+    pub fn save_to(&self, path: &Path) -> Result<(), LeaderboardError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let data = serde_json::to_string_pretty(self)
+            .map_err(|error| LeaderboardError::Invalid(error.to_string()))?;
+
+        Ok(write_atomic(path, data)?)
+    }
+
+    /// Get the entries for a mode, best first.
+    pub fn get_entries(&self, mode: GameMode) -> &[LeaderboardEntry] {
+        self.modes
+            .get(mode.get_id())
+            .map_or(&[], |entries| entries.as_slice())
+    }
+
+    /// Get the index the entry would be ranked at in the given mode, if it would make the leaderboard. Ties rank
+    /// behind the entries that were there first.
+    pub fn get_rank(&self, mode: GameMode, entry: &LeaderboardEntry) -> Option<usize> {
+        let rank = self
+            .get_entries(mode)
+            .iter()
+            .take_while(|other| !entry.get_is_better_than(other, mode))
+            .count();
+
+        (rank < LEADERBOARD_SIZE).then_some(rank)
+    }
+
+    /// Add an entry to the given mode, returning the index it was ranked at if it made the leaderboard.
+    pub fn add_entry(&mut self, mode: GameMode, entry: LeaderboardEntry) -> Option<usize> {
+        let rank = self.get_rank(mode, &entry)?;
+        let entries = self.modes.entry(mode.get_id().to_string()).or_default();
+
+        entries.insert(rank, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+}
@@ -0,0 +1,21 @@
+pub mod ai;
+pub mod attack;
+pub mod bag_manager;
+pub mod block;
+pub mod bot;
+mod byte_reader;
+pub mod finesse;
+pub mod game_mode;
+pub mod game_state;
+pub mod grid;
+pub mod handling;
+pub mod leaderboard;
+pub mod net;
+pub mod piece;
+pub mod placement;
+pub mod randomizer;
+pub mod replay;
+pub mod scoring;
+pub mod stats;
+pub mod storage;
+pub mod versus;
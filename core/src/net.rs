@@ -0,0 +1,766 @@
+use crate::attack::AttackTable;
+use crate::byte_reader::{ByteReader, ReadError};
+use crate::game_state::GameInput;
+use crate::handling::{HandlingConfig, HandlingSetting};
+use crate::randomizer::RandomizerKind;
+use crate::replay::{decode_input, encode_input, RULES_VERSION};
+use crate::versus::{Versus, PLAYER_COUNT};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PORT: u16 = 24816;
+
+const MAGIC: &[u8; 4] = b"BLXN";
+/// Bump this whenever the messages change.
+const PROTOCOL_VERSION: u8 = 1;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+// How long the opponent can go without sending anything before the connection is considered lost. Pings keep
+// the connection busy even while the match is stalled.
+const RECEIVE_TIMEOUT: Duration = Duration::from_secs(10);
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+// How long leaving a match waits for the goodbye to go out, which is called from the game loop.
+const LEAVE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How many ticks after it was pressed an input takes effect. Inputs are sent ahead of time by this much, so
+/// the opponent's input for a tick has usually arrived by the time it's needed, and the match doesn't stall.
+pub const INPUT_DELAY_TICKS: usize = 3;
+
+const MESSAGE_HELLO: u8 = 0;
+const MESSAGE_START: u8 = 1;
+const MESSAGE_READY: u8 = 2;
+const MESSAGE_INPUT: u8 = 3;
+const MESSAGE_PING: u8 = 4;
+const MESSAGE_PONG: u8 = 5;
+const MESSAGE_BYE: u8 = 6;
+
+#[derive(Debug)]
+pub enum NetError {
+    Io(std::io::Error),
+    Invalid(&'static str),
+    /// The other player is running a different version of the protocol or the rules, so the match would desync.
+    VersionMismatch,
+    Disconnected,
+}
+
+impl Display for NetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetError::Io(error) => write!(f, "{}", error),
+            NetError::Invalid(reason) => write!(f, "Invalid message: {}", reason),
+            NetError::VersionMismatch => write!(f, "The other player is on a different version"),
+            NetError::Disconnected => write!(f, "Disconnected"),
+        }
+    }
+}
+
+impl From<std::io::Error> for NetError {
+    fn from(error: std::io::Error) -> Self {
+        NetError::Io(error)
+    }
+}
+
+impl From<ReadError> for NetError {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::UnexpectedEnd => NetError::Invalid("message too short"),
+            ReadError::MalformedString => NetError::Invalid("malformed string"),
+        }
+    }
+}
+
+/// Why a networked match ended before either player topped out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// The opponent left the match.
+    Left,
+    /// The connection dropped, or the opponent stopped responding.
+    ConnectionLost,
+    /// The opponent sent something that doesn't make sense.
+    ProtocolError,
+}
+
+impl DisconnectReason {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            DisconnectReason::Left => "Opponent left",
+            DisconnectReason::ConnectionLost => "Connection lost",
+            DisconnectReason::ProtocolError => "Protocol error",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Message {
+    Hello {
+        protocol_version: u8,
+        rules_version: u32,
+    },
+    /// Sent by the host once both sides have agreed on versions, with everything needed to start the match.
+    Start {
+        seed: u64,
+        randomizer_kind: RandomizerKind,
+        handling: HandlingConfig,
+        attack_table: AttackTable,
+    },
+    /// Sent by the joining player in reply to Start, with their own handling.
+    Ready {
+        handling: HandlingConfig,
+    },
+    /// The sender's input for their next tick. Messages arrive in order, so the tick doesn't need to be sent.
+    Input(GameInput),
+    Ping(u32),
+    Pong(u32),
+    /// The sender is leaving the match.
+    Bye,
+}
+
+fn write_handling(bytes: &mut Vec<u8>, handling: &HandlingConfig) {
+    for setting in HandlingSetting::ALL {
+        bytes.extend_from_slice(&(handling.get_raw(setting) as u32).to_le_bytes());
+    }
+}
+
+fn write_attack_table(bytes: &mut Vec<u8>, attack_table: &AttackTable) {
+    let rows = [
+        attack_table.single,
+        attack_table.double,
+        attack_table.triple,
+        attack_table.tetris,
+        attack_table.t_spin_mini_single,
+        attack_table.t_spin_mini_double,
+        attack_table.t_spin_single,
+        attack_table.t_spin_double,
+        attack_table.t_spin_triple,
+        attack_table.back_to_back_bonus,
+        attack_table.perfect_clear,
+    ];
+
+    for rows in rows.iter().chain(&attack_table.combo_bonuses) {
+        bytes.push(*rows as u8);
+    }
+}
+
+fn read_handling(reader: &mut ByteReader) -> Result<HandlingConfig, NetError> {
+    let mut handling = HandlingConfig::default();
+
+    for setting in HandlingSetting::ALL {
+        handling.set_raw(setting, reader.read_u32()? as u64);
+    }
+
+    Ok(handling)
+}
+
+fn read_attack_table(reader: &mut ByteReader) -> Result<AttackTable, NetError> {
+    let mut rows = || reader.read_u8().map(|rows| rows as usize);
+
+    let mut attack_table = AttackTable {
+        single: rows()?,
+        double: rows()?,
+        triple: rows()?,
+        tetris: rows()?,
+        t_spin_mini_single: rows()?,
+        t_spin_mini_double: rows()?,
+        t_spin_single: rows()?,
+        t_spin_double: rows()?,
+        t_spin_triple: rows()?,
+        back_to_back_bonus: rows()?,
+        perfect_clear: rows()?,
+        ..Default::default()
+    };
+
+    for combo_bonus in attack_table.combo_bonuses.iter_mut() {
+        *combo_bonus = rows()?;
+    }
+
+    Ok(attack_table)
+}
+
+impl Message {
+    /// Encode the message as a frame: the length of the rest of the frame, then a message type and its fields.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0, 0];
+
+        match self {
+            Message::Hello {
+                protocol_version,
+                rules_version,
+            } => {
+                bytes.push(MESSAGE_HELLO);
+                bytes.extend_from_slice(MAGIC);
+                bytes.push(*protocol_version);
+                bytes.extend_from_slice(&rules_version.to_le_bytes());
+            }
+            Message::Start {
+                seed,
+                randomizer_kind,
+                handling,
+                attack_table,
+            } => {
+                let randomizer_id = randomizer_kind.get_id();
+
+                bytes.push(MESSAGE_START);
+                bytes.extend_from_slice(&seed.to_le_bytes());
+                bytes.push(randomizer_id.len() as u8);
+                bytes.extend_from_slice(randomizer_id.as_bytes());
+                write_handling(&mut bytes, handling);
+                write_attack_table(&mut bytes, attack_table);
+            }
+            Message::Ready { handling } => {
+                bytes.push(MESSAGE_READY);
+                write_handling(&mut bytes, handling);
+            }
+            Message::Input(input) => {
+                bytes.push(MESSAGE_INPUT);
+                bytes.extend_from_slice(&encode_input(input).to_le_bytes());
+            }
+            Message::Ping(id) => {
+                bytes.push(MESSAGE_PING);
+                bytes.extend_from_slice(&id.to_le_bytes());
+            }
+            Message::Pong(id) => {
+                bytes.push(MESSAGE_PONG);
+                bytes.extend_from_slice(&id.to_le_bytes());
+            }
+            Message::Bye => bytes.push(MESSAGE_BYE),
+        }
+
+        let length = (bytes.len() - 2) as u16;
+        bytes[..2].copy_from_slice(&length.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a message from a frame, without its length.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, NetError> {
+        let mut reader = ByteReader::new(bytes);
+
+        let message = match reader.read_u8()? {
+            MESSAGE_HELLO => {
+                if reader.take(MAGIC.len())? != MAGIC {
+                    return Err(NetError::Invalid("not a bloxide peer"));
+                }
+
+                Message::Hello {
+                    protocol_version: reader.read_u8()?,
+                    rules_version: reader.read_u32()?,
+                }
+            }
+            MESSAGE_START => {
+                let seed = reader.read_u64()?;
+                let randomizer_id = reader.read_str()?;
+
+                Message::Start {
+                    seed,
+                    randomizer_kind: RandomizerKind::from_id(randomizer_id)
+                        .ok_or(NetError::Invalid("unknown randomizer"))?,
+                    handling: read_handling(&mut reader)?,
+                    attack_table: read_attack_table(&mut reader)?,
+                }
+            }
+            MESSAGE_READY => Message::Ready {
+                handling: read_handling(&mut reader)?,
+            },
+            MESSAGE_INPUT => Message::Input(decode_input(reader.read_u16()?)),
+            MESSAGE_PING => Message::Ping(reader.read_u32()?),
+            MESSAGE_PONG => Message::Pong(reader.read_u32()?),
+            MESSAGE_BYE => Message::Bye,
+            _ => return Err(NetError::Invalid("unknown message type")),
+        };
+
+        Ok(message)
+    }
+}
+
+/// A TCP connection that sends and receives messages. Blocking reads are used for the handshake, which runs on
+/// its own thread, and non-blocking reads and writes once the match has started, so it never holds up a frame.
+struct Connection {
+    stream: TcpStream,
+    read_buffer: Vec<u8>,
+    write_buffer: Vec<u8>,
+    // Whether the other side has closed the connection. Anything it sent before closing can still be received.
+    is_closed: bool,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Self, NetError> {
+        // Inputs are tiny and need to arrive as soon as possible.
+        stream.set_nodelay(true)?;
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        stream.set_write_timeout(Some(HANDSHAKE_TIMEOUT))?;
+
+        Ok(Self {
+            stream,
+            read_buffer: Vec::new(),
+            write_buffer: Vec::new(),
+            is_closed: false,
+        })
+    }
+
+    fn send_blocking(&mut self, message: &Message) -> Result<(), NetError> {
+        self.stream.write_all(&message.to_bytes())?;
+        Ok(())
+    }
+
+    fn receive_blocking(&mut self) -> Result<Message, NetError> {
+        let mut length = [0; 2];
+        self.stream.read_exact(&mut length)?;
+
+        let mut frame = vec![0; u16::from_le_bytes(length) as usize];
+        self.stream.read_exact(&mut frame)?;
+
+        Message::from_bytes(&frame)
+    }
+
+    /// Exchange Hello messages, and check that both sides are playing by the same rules.
+    fn handshake(&mut self) -> Result<(), NetError> {
+        self.send_blocking(&Message::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            rules_version: RULES_VERSION,
+        })?;
+
+        match self.receive_blocking()? {
+            Message::Hello {
+                protocol_version: PROTOCOL_VERSION,
+                rules_version: RULES_VERSION,
+            } => Ok(()),
+            Message::Hello { .. } => Err(NetError::VersionMismatch),
+            _ => Err(NetError::Invalid("expected hello")),
+        }
+    }
+
+    /// Switch to non-blocking mode, for once the match has started.
+    fn set_nonblocking(&mut self) -> Result<(), NetError> {
+        self.stream.set_nonblocking(true)?;
+        Ok(())
+    }
+
+    /// Queue a message to be sent on the next flush.
+    fn send(&mut self, message: &Message) {
+        self.write_buffer.extend_from_slice(&message.to_bytes());
+    }
+
+    /// Write as much of the queued messages as the socket will take without blocking.
+    fn flush(&mut self) -> Result<(), NetError> {
+        while !self.write_buffer.is_empty() {
+            match self.stream.write(&self.write_buffer) {
+                Ok(0) => return Err(NetError::Disconnected),
+                Ok(count) => {
+                    self.write_buffer.drain(..count);
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => (),
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read every complete message that has arrived, without blocking. A closed connection isn't an error
+    /// here, so the messages sent just before it closed aren't lost.
+    fn receive(&mut self) -> Result<Vec<Message>, NetError> {
+        let mut chunk = [0; 1024];
+
+        while !self.is_closed {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => self.is_closed = true,
+                Ok(count) => self.read_buffer.extend_from_slice(&chunk[..count]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => (),
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        let mut messages = Vec::new();
+
+        while self.read_buffer.len() >= 2 {
+            let length = u16::from_le_bytes([self.read_buffer[0], self.read_buffer[1]]) as usize;
+
+            if self.read_buffer.len() < 2 + length {
+                break;
+            }
+
+            messages.push(Message::from_bytes(&self.read_buffer[2..2 + length])?);
+            self.read_buffer.drain(..2 + length);
+        }
+
+        Ok(messages)
+    }
+}
+
+/// Run a handshake on its own thread, since it blocks on the other player. Its result can be picked up from the
+/// returned receiver once it's done.
+fn spawn_handshake(
+    handshake: impl FnOnce() -> Result<NetVersus, NetError> + Send + 'static,
+) -> Receiver<Result<NetVersus, NetError>> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        // Nobody's waiting on the result if the receiver has been dropped, e.g. because joining was cancelled.
+        let _ = sender.send(handshake());
+    });
+
+    receiver
+}
+
+/// Check on a handshake running on its own thread, without blocking.
+fn try_receive_handshake(
+    receiver: &Receiver<Result<NetVersus, NetError>>,
+) -> Option<Result<NetVersus, NetError>> {
+    match receiver.try_recv() {
+        Ok(result) => Some(result),
+        Err(TryRecvError::Empty) => None,
+        // The thread only goes away without sending if it panicked.
+        Err(TryRecvError::Disconnected) => Some(Err(NetError::Disconnected)),
+    }
+}
+
+/// Waits for another player to join a networked versus match.
+pub struct NetHost {
+    listener: TcpListener,
+    seed: u64,
+    randomizer_kind: RandomizerKind,
+    handling: HandlingConfig,
+    attack_table: AttackTable,
+    // The handshake with the player that's joining. Only one player is let in at a time, and anyone else waits
+    // in the listener's backlog until it's done.
+    pending_handshake: Option<Receiver<Result<NetVersus, NetError>>>,
+}
+
+impl NetHost {
+    /// Start listening for a player to join. The host decides the seed, randomizer and attack table for the
+    /// match.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        seed: u64,
+        randomizer_kind: RandomizerKind,
+        handling: HandlingConfig,
+        attack_table: AttackTable,
+    ) -> Result<Self, NetError> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener,
+            seed,
+            randomizer_kind,
+            handling,
+            attack_table,
+            pending_handshake: None,
+        })
+    }
+
+    pub fn get_local_addr(&self) -> Result<SocketAddr, NetError> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Check for a player joining, and return the match once the handshake with them has finished. This never
+    /// blocks: the handshake runs on its own thread, and its result is picked up by a later call. Errors only
+    /// affect the player that tried to join, so the host can keep waiting for another.
+    pub fn accept(&mut self) -> Result<Option<NetVersus>, NetError> {
+        if let Some(receiver) = &self.pending_handshake {
+            let Some(result) = try_receive_handshake(receiver) else {
+                return Ok(None);
+            };
+
+            self.pending_handshake = None;
+            return result.map(Some);
+        }
+
+        let stream = match self.listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        let (seed, randomizer_kind, handling, attack_table) = (
+            self.seed,
+            self.randomizer_kind,
+            self.handling,
+            self.attack_table,
+        );
+
+        self.pending_handshake = Some(spawn_handshake(move || {
+            let mut connection = Connection::new(stream)?;
+            connection.handshake()?;
+            connection.send_blocking(&Message::Start {
+                seed,
+                randomizer_kind,
+                handling,
+                attack_table,
+            })?;
+
+            let Message::Ready {
+                handling: remote_handling,
+            } = connection.receive_blocking()?
+            else {
+                return Err(NetError::Invalid("expected ready"));
+            };
+
+            let versus = Versus::new(
+                randomizer_kind,
+                seed,
+                [handling, remote_handling],
+                attack_table,
+            );
+
+            NetVersus::new(versus, 0, connection)
+        }));
+
+        Ok(None)
+    }
+}
+
+/// A player joining a networked versus match. Connecting and the handshake run on their own thread, so poll this
+/// until the match has started, or failed to.
+pub struct NetJoin {
+    handshake: Receiver<Result<NetVersus, NetError>>,
+}
+
+impl NetJoin {
+    /// Check whether the match has started, without blocking.
+    pub fn poll(&self) -> Result<Option<NetVersus>, NetError> {
+        try_receive_handshake(&self.handshake).transpose()
+    }
+}
+
+/// Start joining a networked versus match hosted at the given address.
+pub fn join(addr: impl ToSocketAddrs + Send + 'static, handling: HandlingConfig) -> NetJoin {
+    let handshake = spawn_handshake(move || {
+        let addr = addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or(NetError::Invalid("no address to connect to"))?;
+
+        let mut connection = Connection::new(TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?)?;
+        connection.handshake()?;
+
+        let Message::Start {
+            seed,
+            randomizer_kind,
+            handling: remote_handling,
+            attack_table,
+        } = connection.receive_blocking()?
+        else {
+            return Err(NetError::Invalid("expected start"));
+        };
+
+        connection.send_blocking(&Message::Ready { handling })?;
+
+        let versus = Versus::new(
+            randomizer_kind,
+            seed,
+            [remote_handling, handling],
+            attack_table,
+        );
+
+        NetVersus::new(versus, 1, connection)
+    });
+
+    NetJoin { handshake }
+}
+
+/// A versus match against a player on another machine, kept in sync by lockstep: both sides send their input
+/// for every tick, and a tick only runs once both inputs for it are known. Garbage never has to be sent, since
+/// both sides run the same simulation with the same inputs.
+pub struct NetVersus {
+    pub versus: Versus,
+    local_index: usize,
+    connection: Connection,
+    // Inputs for the upcoming ticks, oldest first
+    local_inputs: VecDeque<GameInput>,
+    remote_inputs: VecDeque<GameInput>,
+    disconnect_reason: Option<DisconnectReason>,
+    last_received_at: Instant,
+    last_ping_at: Instant,
+    next_ping_id: u32,
+    // The ping that's waiting for a reply, as (id, time it was sent)
+    pending_ping: Option<(u32, Instant)>,
+    latency: Option<Duration>,
+}
+
+impl NetVersus {
+    fn new(
+        versus: Versus,
+        local_index: usize,
+        mut connection: Connection,
+    ) -> Result<Self, NetError> {
+        connection.set_nonblocking()?;
+
+        // Both sides start with the same run of empty inputs, covering the ticks before the first real inputs
+        // take effect.
+        let delay_inputs: VecDeque<GameInput> =
+            std::iter::repeat_n(GameInput::default(), INPUT_DELAY_TICKS).collect();
+
+        Ok(Self {
+            versus,
+            local_index,
+            connection,
+            local_inputs: delay_inputs.clone(),
+            remote_inputs: delay_inputs,
+            disconnect_reason: None,
+            last_received_at: Instant::now(),
+            last_ping_at: Instant::now(),
+            next_ping_id: 0,
+            pending_ping: None,
+            latency: None,
+        })
+    }
+
+    /// Send the local input for the next tick, and run every tick that both inputs have arrived for. Returns
+    /// whether the input was used. It isn't while this side is too far ahead of the opponent, in which case it
+    /// should be passed in again on the next tick.
+    pub fn step(&mut self, local_input: GameInput) -> bool {
+        if self.get_is_over() {
+            return true;
+        }
+
+        self.poll();
+
+        let is_input_used = self.local_inputs.len() <= INPUT_DELAY_TICKS;
+
+        if is_input_used {
+            let local_input = GameInput {
+                toggle_pause: false,
+                ..local_input
+            };
+
+            self.connection.send(&Message::Input(local_input));
+            self.local_inputs.push_back(local_input);
+        }
+
+        if !self.remote_inputs.is_empty() && !self.local_inputs.is_empty() {
+            let mut inputs = [GameInput::default(); PLAYER_COUNT];
+            inputs[self.local_index] = self.local_inputs.pop_front().unwrap();
+            inputs[1 - self.local_index] = self.remote_inputs.pop_front().unwrap();
+
+            self.versus.step(inputs);
+        }
+
+        self.flush();
+
+        is_input_used
+    }
+
+    /// Handle everything the opponent has sent, and keep the latency measurement going.
+    fn poll(&mut self) {
+        if self.disconnect_reason.is_some() {
+            return;
+        }
+
+        match self.connection.receive() {
+            Ok(messages) => {
+                if !messages.is_empty() {
+                    self.last_received_at = Instant::now();
+                }
+
+                for message in messages {
+                    self.handle_message(message);
+                }
+
+                if self.connection.is_closed {
+                    self.disconnect(DisconnectReason::ConnectionLost);
+                }
+            }
+            Err(NetError::Invalid(_)) => self.disconnect(DisconnectReason::ProtocolError),
+            Err(_) => self.disconnect(DisconnectReason::ConnectionLost),
+        }
+
+        if self.disconnect_reason.is_none() && self.last_received_at.elapsed() > RECEIVE_TIMEOUT {
+            self.disconnect(DisconnectReason::ConnectionLost);
+        }
+
+        if self.pending_ping.is_none() && self.last_ping_at.elapsed() >= PING_INTERVAL {
+            self.connection.send(&Message::Ping(self.next_ping_id));
+            self.pending_ping = Some((self.next_ping_id, Instant::now()));
+            self.last_ping_at = Instant::now();
+            self.next_ping_id = self.next_ping_id.wrapping_add(1);
+        }
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Input(input) => self.remote_inputs.push_back(input),
+            Message::Ping(id) => self.connection.send(&Message::Pong(id)),
+            Message::Pong(id) => {
+                if let Some((pending_id, sent_at)) = self.pending_ping {
+                    if pending_id == id {
+                        self.latency = Some(sent_at.elapsed());
+                        self.pending_ping = None;
+                    }
+                }
+            }
+            Message::Bye => self.disconnect(DisconnectReason::Left),
+            Message::Hello { .. } | Message::Start { .. } | Message::Ready { .. } => {
+                self.disconnect(DisconnectReason::ProtocolError)
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.disconnect_reason.is_none() && self.connection.flush().is_err() {
+            self.disconnect(DisconnectReason::ConnectionLost);
+        }
+    }
+
+    fn disconnect(&mut self, reason: DisconnectReason) {
+        if self.disconnect_reason.is_none() {
+            self.disconnect_reason = Some(reason);
+            let _ = self.connection.stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Leave the match, letting the opponent know.
+    pub fn leave(&mut self) {
+        if self.disconnect_reason.is_some() {
+            return;
+        }
+
+        self.connection.send(&Message::Bye);
+
+        // Give the goodbye a moment to go out, since nothing will flush it later, but not so long that a gone
+        // opponent stalls the game.
+        let _ = self
+            .connection
+            .stream
+            .set_write_timeout(Some(LEAVE_TIMEOUT));
+        let _ = self.connection.stream.set_nonblocking(false);
+        let _ = self.connection.flush();
+        let _ = self.connection.stream.shutdown(Shutdown::Both);
+    }
+
+    /// Whether the match has finished, either because a player topped out or because of a disconnect. After a
+    /// disconnect, the match keeps going until it runs out of the opponent's inputs, since they may have been
+    /// enough for someone to top out.
+    pub fn get_is_over(&self) -> bool {
+        self.versus.get_is_over()
+            || (self.disconnect_reason.is_some() && self.remote_inputs.is_empty())
+    }
+
+    /// Get the index of the local player in the match. The host is always the first player.
+    pub fn get_local_index(&self) -> usize {
+        self.local_index
+    }
+
+    pub fn get_disconnect_reason(&self) -> Option<DisconnectReason> {
+        self.disconnect_reason
+    }
+
+    /// Get the most recently measured round trip time to the opponent, once there is one.
+    pub fn get_latency(&self) -> Option<Duration> {
+        self.latency
+    }
+
+    /// Get the number of ticks this side is ahead of the opponent, i.e. how many of its inputs are waiting on
+    /// the opponent's.
+    pub fn get_ticks_ahead(&self) -> usize {
+        self.local_inputs.len().saturating_sub(INPUT_DELAY_TICKS)
+    }
+}
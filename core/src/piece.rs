@@ -1,11 +1,45 @@
-use crate::block::Block;
+use crate::block::{Block, BlockColor};
 use crate::grid::GRID_COUNT_COLS;
-use macroquad::prelude::Color;
 use std::fmt::Display;
 
 /// Fixed-size block canvas - avoids heap allocation
 pub type BlockCanvas = [[Option<Block>; 5]; 5];
 
+/// Maximum number of kick tests for a single rotation. The offset tables have 5 entries, and the 180
+/// kick table has 6.
+pub const MAX_KICK_TESTS: usize = 6;
+
+/// Fixed-size list of kick tests, as (col, row) offsets in Guideline coordinates (rows counted upward).
+pub type KickTests = [(isize, isize); MAX_KICK_TESTS];
+
+/// Kicks for 180 rotations, indexed by the starting orientation. These aren't part of the Guideline, so
+/// these are the commonly-used tables from TETR.IO (SRS+). They're applied on top of the difference
+/// between the first offsets of each orientation, so the I and O pieces rotate about the right center.
+const KICKS_180: [KickTests; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+impl Rotation {
+    /// Get the orientation that results from applying this rotation to the given orientation.
+    pub fn apply(&self, orientation: usize) -> usize {
+        match self {
+            Rotation::Clockwise => (orientation + 1) % 4,
+            Rotation::CounterClockwise => (orientation + 3) % 4,
+            Rotation::Half => (orientation + 2) % 4,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default)]
 pub struct OrientationDef {
     pub blocks: [[usize; 5]; 5],
@@ -17,7 +51,7 @@ pub struct OrientationDef {
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Piece {
     pub name: &'static str,
-    pub color: Color,
+    pub color: BlockColor,
     pub bounds_width: usize,
     pub bounds_height: usize,
     pub orientations: [OrientationDef; 4],
@@ -30,9 +64,13 @@ impl Piece {
         let orientation_def = self.orientations[orientation % 4];
         let mut canvas: BlockCanvas = [[None; 5]; 5];
 
-        for row in 0..self.bounds_height {
-            for col in 0..self.bounds_width {
-                canvas[row][col] = match orientation_def.blocks[row][col] {
+        for (canvas_row, block_row) in canvas
+            .iter_mut()
+            .zip(orientation_def.blocks)
+            .take(self.bounds_height)
+        {
+            for (cell, block) in canvas_row.iter_mut().zip(block_row).take(self.bounds_width) {
+                *cell = match block {
                     0 => None,
                     _ => Some(Block::new(self.color)),
                 };
@@ -53,6 +91,46 @@ impl Piece {
         )
     }
 
+    /// Get the kick tests to try, in order, when rotating from the given orientation.
+    /// Returns the tests along with how many of them are valid.
+    ///
+    /// Clockwise and counter-clockwise kicks are derived from the offset tables, by subtracting the
+    /// offsets of the target orientation from those of the starting orientation. 180 kicks use a
+    /// dedicated table, since the offset tables only describe quarter turns.
+    pub fn get_kick_tests(&self, orientation: usize, rotation: Rotation) -> (KickTests, usize) {
+        let next_orientation = rotation.apply(orientation);
+        let offsets_a = self.orientations[orientation % 4].offsets;
+        let offsets_b = self.orientations[next_orientation].offsets;
+        let mut tests: KickTests = [(0, 0); MAX_KICK_TESTS];
+
+        match rotation {
+            Rotation::Clockwise | Rotation::CounterClockwise => {
+                for index in 0..offsets_a.len() {
+                    tests[index] = (
+                        offsets_a[index].0 - offsets_b[index].0,
+                        offsets_a[index].1 - offsets_b[index].1,
+                    );
+                }
+
+                (tests, offsets_a.len())
+            }
+
+            Rotation::Half => {
+                let base_col = offsets_a[0].0 - offsets_b[0].0;
+                let base_row = offsets_a[0].1 - offsets_b[0].1;
+
+                // The O piece can't be kicked; it only needs the base offset to stay in place.
+                let test_count = if self.name == "O" { 1 } else { MAX_KICK_TESTS };
+
+                for (index, kick) in KICKS_180[orientation % 4].iter().enumerate() {
+                    tests[index] = (base_col + kick.0, base_row + kick.1);
+                }
+
+                (tests, test_count)
+            }
+        }
+    }
+
     pub fn get_initial_col(&self) -> isize {
         ((GRID_COUNT_COLS - self.bounds_width) / 2) as isize
     }
@@ -66,15 +144,17 @@ impl Display for Piece {
 
 pub mod pieces {
     use super::{OrientationDef, Piece};
-    use macroquad::{color_u8, prelude::Color};
+    use crate::block::BlockColor;
+
+    pub const PIECE_COLOR_I: BlockColor = BlockColor::new(100, 196, 235);
+    pub const PIECE_COLOR_J: BlockColor = BlockColor::new(92, 101, 168);
+    pub const PIECE_COLOR_L: BlockColor = BlockColor::new(224, 127, 58);
+    pub const PIECE_COLOR_O: BlockColor = BlockColor::new(241, 212, 72);
+    pub const PIECE_COLOR_S: BlockColor = BlockColor::new(100, 180, 82);
+    pub const PIECE_COLOR_T: BlockColor = BlockColor::new(140, 26, 245);
+    pub const PIECE_COLOR_Z: BlockColor = BlockColor::new(234, 51, 35);
 
-    pub const PIECE_COLOR_I: Color = color_u8!(100, 196, 235, 255);
-    pub const PIECE_COLOR_J: Color = color_u8!(92, 101, 168, 255);
-    pub const PIECE_COLOR_L: Color = color_u8!(224, 127, 58, 255);
-    pub const PIECE_COLOR_O: Color = color_u8!(241, 212, 72, 255);
-    pub const PIECE_COLOR_S: Color = color_u8!(100, 180, 82, 255);
-    pub const PIECE_COLOR_T: Color = color_u8!(140, 26, 245, 255);
-    pub const PIECE_COLOR_Z: Color = color_u8!(234, 51, 35, 255);
+    pub const ALL: [Piece; 7] = [I, J, L, O, S, T, Z];
 
     pub const I: Piece = Piece {
         name: "I",
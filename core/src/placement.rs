@@ -0,0 +1,283 @@
+use crate::block::Block;
+use crate::game_state::{GameInput, GameState};
+use crate::grid::Grid;
+use crate::piece::{Piece, Rotation};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// Far more ticks than it should ever take to move a piece into place. Plans that take longer than this are
+// given up on, e.g. when the handling makes a column impossible to stop at.
+const MAX_PLAN_TICKS: usize = 60 * 10;
+
+/// Where a piece is on the grid: the row and column of the top-left of its block canvas, and its orientation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PiecePosition {
+    pub row: isize,
+    pub col: isize,
+    pub orientation: usize,
+}
+
+impl PiecePosition {
+    /// Get the grid cells the given piece covers in this position, as (row, col).
+    pub fn get_cells(&self, piece: &Piece) -> Vec<(isize, isize)> {
+        let (blocks, height, width) = piece.get_blocks(self.orientation);
+        let mut cells = Vec::new();
+
+        for (row, block_row) in blocks.iter().enumerate().take(height) {
+            for (col, block) in block_row.iter().enumerate().take(width) {
+                if block.is_some() {
+                    cells.push((self.row + row as isize, self.col + col as isize));
+                }
+            }
+        }
+
+        cells
+    }
+
+    pub fn collides(&self, grid: &Grid, piece: &Piece) -> bool {
+        let (blocks, height, width) = piece.get_blocks(self.orientation);
+        grid.collision_check(self.row, self.col, &blocks, height, width)
+    }
+
+    /// Lock the piece into the grid in this position, and clear any rows it fills. Returns the number of rows
+    /// cleared.
+    pub fn place(&self, grid: &mut Grid, piece: &Piece) -> usize {
+        for (row, col) in self.get_cells(piece) {
+            grid.set_cell(row as usize, col as usize, Some(Block::new(piece.color)));
+        }
+
+        grid.clear_all_filled_rows()
+    }
+
+    /// Get the position the piece would end up in if it were dropped straight down from here.
+    pub fn get_landing_position(&self, grid: &Grid, piece: &Piece) -> Self {
+        let (blocks, height, width) = piece.get_blocks(self.orientation);
+
+        Self {
+            row: grid.find_landing_row(self.row, self.col, &blocks, height, width),
+            ..*self
+        }
+    }
+}
+
+/// A single movement of the active piece, following the same rules as the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    ShiftLeft,
+    ShiftRight,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    /// Drop straight to the floor, without locking.
+    SoftDrop,
+}
+
+impl Move {
+    pub const ALL: [Move; 6] = [
+        Move::ShiftLeft,
+        Move::ShiftRight,
+        Move::RotateClockwise,
+        Move::RotateCounterClockwise,
+        Move::Rotate180,
+        Move::SoftDrop,
+    ];
+
+    /// Get where the piece ends up after this move, or None if the move isn't possible.
+    pub fn apply(
+        &self,
+        grid: &Grid,
+        piece: &Piece,
+        position: PiecePosition,
+    ) -> Option<PiecePosition> {
+        let rotation = match self {
+            Move::ShiftLeft | Move::ShiftRight => {
+                let next_position = PiecePosition {
+                    col: position.col + if *self == Move::ShiftLeft { -1 } else { 1 },
+                    ..position
+                };
+
+                return (!next_position.collides(grid, piece)).then_some(next_position);
+            }
+            Move::SoftDrop => {
+                let next_position = position.get_landing_position(grid, piece);
+                return (next_position != position).then_some(next_position);
+            }
+            Move::RotateClockwise => Rotation::Clockwise,
+            Move::RotateCounterClockwise => Rotation::CounterClockwise,
+            Move::Rotate180 => Rotation::Half,
+        };
+
+        let orientation = rotation.apply(position.orientation);
+        let (kick_tests, kick_test_count) = piece.get_kick_tests(position.orientation, rotation);
+
+        // Kick offsets count rows upward, like the Guideline, so they're subtracted from the row.
+        kick_tests[..kick_test_count]
+            .iter()
+            .map(|&(offset_col, offset_row)| PiecePosition {
+                row: position.row - offset_row,
+                col: position.col + offset_col,
+                orientation,
+            })
+            .find(|next_position| !next_position.collides(grid, piece))
+    }
+
+    /// Get the input that makes this move.
+    pub fn get_input(&self) -> GameInput {
+        GameInput {
+            shift_left: *self == Move::ShiftLeft,
+            shift_right: *self == Move::ShiftRight,
+            rotate_right: *self == Move::RotateClockwise,
+            rotate_left: *self == Move::RotateCounterClockwise,
+            rotate_180: *self == Move::Rotate180,
+            soft_drop: *self == Move::SoftDrop,
+            ..Default::default()
+        }
+    }
+}
+
+/// Find the shortest list of moves that takes the piece from one position to another, searching breadth
+/// first. None if the target can't be reached.
+pub fn find_path(
+    grid: &Grid,
+    piece: &Piece,
+    from: PiecePosition,
+    to: PiecePosition,
+) -> Option<Vec<Move>> {
+    // Every position that's been reached, along with the move and position it was reached from
+    let mut previous: HashMap<PiecePosition, Option<(Move, PiecePosition)>> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    previous.insert(from, None);
+
+    while let Some(position) = queue.pop_front() {
+        if position == to {
+            let mut moves = Vec::new();
+            let mut current = position;
+
+            while let Some(&Some((previous_move, previous_position))) = previous.get(&current) {
+                moves.push(previous_move);
+                current = previous_position;
+            }
+
+            moves.reverse();
+            return Some(moves);
+        }
+
+        for next_move in Move::ALL {
+            if let Some(next_position) = next_move.apply(grid, piece, position) {
+                previous.entry(next_position).or_insert_with(|| {
+                    queue.push_back(next_position);
+                    Some((next_move, position))
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Find every resting position the piece can be moved into from the given position. Positions that cover the
+/// same cells (e.g. an O piece in each of its orientations) are only listed once.
+pub fn find_placements(grid: &Grid, piece: &Piece, from: PiecePosition) -> Vec<PiecePosition> {
+    let mut visited = HashSet::from([from]);
+    let mut queue = VecDeque::from([from]);
+    let mut placed_cells = HashSet::new();
+    let mut placements = Vec::new();
+
+    while let Some(position) = queue.pop_front() {
+        if position.get_landing_position(grid, piece) == position {
+            let mut cells = position.get_cells(piece);
+            cells.sort();
+
+            if placed_cells.insert(cells) {
+                placements.push(position);
+            }
+        }
+
+        for next_move in Move::ALL {
+            if let Some(next_position) = next_move.apply(grid, piece, position) {
+                if visited.insert(next_position) {
+                    queue.push_back(next_position);
+                }
+            }
+        }
+    }
+
+    placements
+}
+
+/// Work out the inputs that put the active piece in the given resting position and hard drop it there,
+/// holding first if asked to. The inputs are tried out on a copy of the game as they're planned, so they take
+/// the game's handling and gravity into account. None if the position can't be reached.
+pub fn plan_inputs(
+    game_state: &GameState,
+    target: PiecePosition,
+    is_hold: bool,
+) -> Option<Vec<GameInput>> {
+    let mut game_state = game_state.clone_without_replay();
+    let mut inputs = Vec::new();
+
+    if is_hold {
+        if !game_state.get_is_hold_available() {
+            return None;
+        }
+
+        let input = GameInput {
+            hold_piece: true,
+            ..Default::default()
+        };
+
+        game_state.step(input);
+        inputs.push(input);
+    }
+
+    let piece = game_state.get_active_piece();
+
+    if target.get_landing_position(game_state.get_grid_locked(), &piece) != target {
+        return None;
+    }
+
+    let mut last_input = GameInput::default();
+
+    for _ in 0..MAX_PLAN_TICKS {
+        if !game_state.get_is_piece_active() {
+            return None;
+        }
+
+        // Plan again from wherever the piece is on every tick, since gravity and auto repeat can move it
+        // somewhere other than where the last move was meant to.
+        let path = find_path(
+            game_state.get_grid_locked(),
+            &piece,
+            game_state.get_active_piece_position(),
+            target,
+        )?;
+
+        let input = match path.first() {
+            None => GameInput {
+                hard_drop: true,
+                ..Default::default()
+            },
+            Some(next_move) => {
+                let input = next_move.get_input();
+
+                // Shifts have to be released between taps, or they'd count as being held.
+                if (input.shift_left && last_input.shift_left)
+                    || (input.shift_right && last_input.shift_right)
+                {
+                    GameInput::default()
+                } else {
+                    input
+                }
+            }
+        };
+
+        game_state.step(input);
+        inputs.push(input);
+        last_input = input;
+
+        if input.hard_drop {
+            return Some(inputs);
+        }
+    }
+
+    None
+}
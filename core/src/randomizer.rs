@@ -0,0 +1,331 @@
+use crate::bag_manager::BagManager;
+use crate::piece::{pieces, Piece};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// How many pieces past the next one every randomizer is able to peek.
+pub const MAX_PEEK: usize = 7;
+
+// Indexes into pieces::ALL, used by the generators below.
+const PIECE_S: usize = 4;
+const PIECE_Z: usize = 6;
+// Pieces that the TGM randomizers can deal first: I, J, L, and T. Never S, Z, or O.
+const TGM_FIRST_PIECES: [usize; 4] = [0, 1, 2, 5];
+
+/// A source of pieces. Randomizers are Send, so games can be run off the main thread.
+pub trait Randomizer: Debug + Send {
+    /// Take the next piece.
+    fn next(&mut self) -> Piece;
+
+    /// Look at an upcoming piece without taking it. An offset of 1 is the piece that the next call to next()
+    /// will return. Offsets up to MAX_PEEK are supported, and panics on any offset outside 1 to MAX_PEEK.
+    fn peek(&self, offset: usize) -> Piece;
+
+    fn box_clone(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    Memoryless,
+    Nes,
+    Tgm1,
+    Tgm2,
+    Tgm3,
+}
+
+impl RandomizerKind {
+    pub const ALL: [RandomizerKind; 7] = [
+        RandomizerKind::SevenBag,
+        RandomizerKind::FourteenBag,
+        RandomizerKind::Memoryless,
+        RandomizerKind::Nes,
+        RandomizerKind::Tgm1,
+        RandomizerKind::Tgm2,
+        RandomizerKind::Tgm3,
+    ];
+
+    pub fn create(&self, seed: u64) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(BagManager::with_seed(seed)),
+            RandomizerKind::FourteenBag => Box::new(Queued::new(MultiBag::new(2), seed)),
+            RandomizerKind::Memoryless => Box::new(Queued::new(Memoryless, seed)),
+            RandomizerKind::Nes => Box::new(Queued::new(Nes::new(), seed)),
+            RandomizerKind::Tgm1 => Box::new(Queued::new(History::new(4, [PIECE_Z; 4]), seed)),
+            RandomizerKind::Tgm2 => Box::new(Queued::new(
+                History::new(6, [PIECE_Z, PIECE_S, PIECE_S, PIECE_Z]),
+                seed,
+            )),
+            RandomizerKind::Tgm3 => Box::new(Queued::new(DroughtPool::new(), seed)),
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7-Bag",
+            RandomizerKind::FourteenBag => "14-Bag",
+            RandomizerKind::Memoryless => "Memoryless",
+            RandomizerKind::Nes => "NES",
+            RandomizerKind::Tgm1 => "TGM (4 Rolls)",
+            RandomizerKind::Tgm2 => "TGM (6 Rolls)",
+            RandomizerKind::Tgm3 => "TGM3 (35-Bag)",
+        }
+    }
+
+    pub fn get_id(&self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7bag",
+            RandomizerKind::FourteenBag => "14bag",
+            RandomizerKind::Memoryless => "memoryless",
+            RandomizerKind::Nes => "nes",
+            RandomizerKind::Tgm1 => "tgm1",
+            RandomizerKind::Tgm2 => "tgm2",
+            RandomizerKind::Tgm3 => "tgm3",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.get_id() == id)
+    }
+}
+
+pub(crate) fn assert_peek_offset(offset: usize) {
+    assert!(
+        (1..=MAX_PEEK).contains(&offset),
+        "peek offset must be from 1 to {}, got {}",
+        MAX_PEEK,
+        offset
+    );
+}
+
+/// A generator deals pieces one at a time, as indexes into pieces::ALL. Unlike a Randomizer, it can't see
+/// ahead, so it's wrapped in a Queued to support peeking.
+trait Generator: Clone + Debug + Send {
+    fn generate(&mut self, rng: &mut ChaCha8Rng) -> usize;
+}
+
+/// Wraps a generator with a queue of upcoming pieces, so they can be peeked.
+#[derive(Clone, Debug)]
+struct Queued<G: Generator> {
+    generator: G,
+    queue: VecDeque<usize>,
+    rng: ChaCha8Rng,
+}
+
+impl<G: Generator> Queued<G> {
+    fn new(mut generator: G, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let queue = (0..MAX_PEEK)
+            .map(|_| generator.generate(&mut rng))
+            .collect();
+
+        Self {
+            generator,
+            queue,
+            rng,
+        }
+    }
+}
+
+impl<G: Generator + 'static> Randomizer for Queued<G> {
+    fn next(&mut self) -> Piece {
+        let next = self.generator.generate(&mut self.rng);
+        self.queue.push_back(next);
+
+        pieces::ALL[self.queue.pop_front().unwrap()]
+    }
+
+    fn peek(&self, offset: usize) -> Piece {
+        assert_peek_offset(offset);
+        pieces::ALL[self.queue[offset - 1]]
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// A bag containing the given number of copies of each piece, refilled once empty.
+#[derive(Clone, Debug)]
+struct MultiBag {
+    copies: usize,
+    bag: Vec<usize>,
+}
+
+impl MultiBag {
+    fn new(copies: usize) -> Self {
+        Self {
+            copies,
+            bag: Vec::new(),
+        }
+    }
+}
+
+impl Generator for MultiBag {
+    fn generate(&mut self, rng: &mut ChaCha8Rng) -> usize {
+        if self.bag.is_empty() {
+            self.bag = (0..pieces::ALL.len())
+                .flat_map(|piece| std::iter::repeat_n(piece, self.copies))
+                .collect();
+            self.bag.shuffle(rng);
+        }
+
+        self.bag.pop().unwrap()
+    }
+}
+
+/// Every piece is equally likely, every time.
+#[derive(Clone, Debug)]
+struct Memoryless;
+
+impl Generator for Memoryless {
+    fn generate(&mut self, rng: &mut ChaCha8Rng) -> usize {
+        rng.gen_range(0..pieces::ALL.len())
+    }
+}
+
+/// The NES randomizer rolls an 8-sided die, where the 8th side means "reroll". If the roll is a reroll or a
+/// repeat of the last piece, it rolls once more (with a 7-sided die), and takes whatever comes up.
+#[derive(Clone, Debug)]
+struct Nes {
+    last: Option<usize>,
+}
+
+impl Nes {
+    fn new() -> Self {
+        Self { last: None }
+    }
+}
+
+impl Generator for Nes {
+    fn generate(&mut self, rng: &mut ChaCha8Rng) -> usize {
+        let mut piece = rng.gen_range(0..pieces::ALL.len() + 1);
+
+        if piece == pieces::ALL.len() || Some(piece) == self.last {
+            piece = rng.gen_range(0..pieces::ALL.len());
+        }
+
+        self.last = Some(piece);
+        piece
+    }
+}
+
+/// The TGM randomizer keeps a history of the last 4 pieces, and rerolls up to a fixed number of times to avoid
+/// dealing a piece that's in the history. TGM used 4 rolls, and TGM2 used 6.
+#[derive(Clone, Debug)]
+struct History {
+    rolls: usize,
+    history: [usize; 4],
+    is_first: bool,
+}
+
+impl History {
+    fn new(rolls: usize, history: [usize; 4]) -> Self {
+        Self {
+            rolls,
+            history,
+            is_first: true,
+        }
+    }
+
+    fn push_history(&mut self, piece: usize) {
+        self.history.rotate_left(1);
+        self.history[3] = piece;
+    }
+}
+
+impl Generator for History {
+    fn generate(&mut self, rng: &mut ChaCha8Rng) -> usize {
+        if self.is_first {
+            self.is_first = false;
+            let piece = *TGM_FIRST_PIECES.choose(rng).unwrap();
+            self.push_history(piece);
+            return piece;
+        }
+
+        let mut piece = rng.gen_range(0..pieces::ALL.len());
+
+        for _ in 1..self.rolls {
+            if !self.history.contains(&piece) {
+                break;
+            }
+
+            piece = rng.gen_range(0..pieces::ALL.len());
+        }
+
+        self.push_history(piece);
+        piece
+    }
+}
+
+/// The TGM3 randomizer draws from a pool of 35 pieces (5 of each), with a 4-piece history and up to 6 rolls.
+/// Whenever a piece is drawn, or rerolled, its slot in the pool is replaced with the piece that has gone the
+/// longest without being dealt, which evens out droughts.
+#[derive(Clone, Debug)]
+struct DroughtPool {
+    pool: [usize; 35],
+    // Pieces ordered from the longest since last dealt, to the most recently dealt
+    drought_order: Vec<usize>,
+    history: [usize; 4],
+    is_first: bool,
+}
+
+impl DroughtPool {
+    fn new() -> Self {
+        Self {
+            pool: std::array::from_fn(|index| index % pieces::ALL.len()),
+            // J, I, Z, L, O, T, S
+            drought_order: vec![1, 0, 6, 2, 3, 5, 4],
+            history: [PIECE_S, PIECE_Z, PIECE_S, PIECE_Z],
+            is_first: true,
+        }
+    }
+
+    fn deal(&mut self, piece: usize) {
+        self.drought_order.retain(|&other| other != piece);
+        self.drought_order.push(piece);
+        self.history.rotate_left(1);
+        self.history[3] = piece;
+    }
+}
+
+impl Generator for DroughtPool {
+    fn generate(&mut self, rng: &mut ChaCha8Rng) -> usize {
+        if self.is_first {
+            self.is_first = false;
+            let piece = *TGM_FIRST_PIECES.choose(rng).unwrap();
+            self.deal(piece);
+            return piece;
+        }
+
+        let mut index = 0;
+        let mut piece = 0;
+
+        for roll in 0..6 {
+            index = rng.gen_range(0..self.pool.len());
+            piece = self.pool[index];
+
+            if !self.history.contains(&piece) || roll == 5 {
+                break;
+            }
+
+            // Rerolled pieces are swapped out for the most droughted piece, making it more likely next roll.
+            self.pool[index] = self.drought_order[0];
+        }
+
+        self.deal(piece);
+        self.pool[index] = self.drought_order[0];
+
+        piece
+    }
+}
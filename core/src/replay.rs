@@ -0,0 +1,408 @@
+use crate::byte_reader::{ByteReader, ReadError};
+use crate::game_mode::GameMode;
+use crate::game_state::{GameInput, GameState};
+use crate::handling::{HandlingConfig, HandlingSetting};
+use crate::randomizer::RandomizerKind;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bump this whenever a change to the rules would make old replays play out differently. Replays recorded
+/// under a different version can still be played back, but are likely to diverge.
+pub const RULES_VERSION: u32 = 3;
+
+pub const REPLAYS_DIR: &str = "./replays";
+const REPLAY_EXTENSION: &str = "bxr";
+const MAGIC: &[u8; 4] = b"BLXR";
+const FORMAT_VERSION: u8 = 2;
+// Replays longer than this, six hours of play, are treated as corrupt rather than loaded. Inputs are
+// run-length encoded, so a small file could otherwise claim billions of them.
+const MAX_INPUTS: usize = 6 * 60 * 60 * 60;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Invalid(&'static str),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "{}", error),
+            ReplayError::Invalid(reason) => write!(f, "Invalid replay: {}", reason),
+        }
+    }
+}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(error: std::io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+impl From<ReadError> for ReplayError {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::UnexpectedEnd => ReplayError::Invalid("unexpected end of file"),
+            ReadError::MalformedString => ReplayError::Invalid("malformed string"),
+        }
+    }
+}
+
+/// Everything needed to play a game back exactly: the settings it was started with, and the input for every
+/// tick that it advanced.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub rules_version: u32,
+    pub mode: GameMode,
+    pub randomizer_kind: RandomizerKind,
+    pub seed: u64,
+    pub handling: HandlingConfig,
+    pub score: usize,
+    pub rows_cleared: usize,
+    /// Whether the game was over when the replay was saved, rather than left part way through
+    pub is_game_over: bool,
+    pub inputs: Vec<GameInput>,
+    pub checkpoints: Vec<(usize, u64)>,
+}
+
+pub(crate) fn encode_input(input: &GameInput) -> u16 {
+    [
+        input.soft_drop,
+        input.shift_left,
+        input.shift_right,
+        input.rotate_right,
+        input.rotate_left,
+        input.rotate_180,
+        input.hard_drop,
+        input.hold_piece,
+        input.rotate_right_held,
+        input.rotate_left_held,
+        input.rotate_180_held,
+        input.hold_piece_held,
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |bits, (index, &is_set)| {
+        bits | ((is_set as u16) << index)
+    })
+}
+
+pub(crate) fn decode_input(bits: u16) -> GameInput {
+    let is_set = |index: u16| bits & (1 << index) != 0;
+
+    GameInput {
+        soft_drop: is_set(0),
+        shift_left: is_set(1),
+        shift_right: is_set(2),
+        rotate_right: is_set(3),
+        rotate_left: is_set(4),
+        rotate_180: is_set(5),
+        hard_drop: is_set(6),
+        hold_piece: is_set(7),
+        toggle_pause: false,
+        rotate_right_held: is_set(8),
+        rotate_left_held: is_set(9),
+        rotate_180_held: is_set(10),
+        hold_piece_held: is_set(11),
+    }
+}
+
+impl Replay {
+    /// Encode the replay in a compact binary format. Inputs are run-length encoded, since they rarely change
+    /// from one tick to the next.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&self.rules_version.to_le_bytes());
+
+        for id in [self.mode.get_id(), self.randomizer_kind.get_id()] {
+            bytes.push(id.len() as u8);
+            bytes.extend_from_slice(id.as_bytes());
+        }
+
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+
+        for setting in HandlingSetting::ALL {
+            bytes.extend_from_slice(&(self.handling.get_raw(setting) as u32).to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.score as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.rows_cleared as u32).to_le_bytes());
+        bytes.push(self.is_game_over as u8);
+
+        let mut runs: Vec<(u16, u32)> = Vec::new();
+
+        for input in &self.inputs {
+            let bits = encode_input(input);
+
+            match runs.last_mut() {
+                Some((last_bits, count)) if *last_bits == bits => *count += 1,
+                _ => runs.push((bits, 1)),
+            }
+        }
+
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+
+        for (bits, count) in runs {
+            bytes.extend_from_slice(&bits.to_le_bytes());
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.checkpoints.len() as u32).to_le_bytes());
+
+        for (tick, hash) in &self.checkpoints {
+            bytes.extend_from_slice(&(*tick as u32).to_le_bytes());
+            bytes.extend_from_slice(&hash.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = ByteReader::new(bytes);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(ReplayError::Invalid("not a replay file"));
+        }
+
+        if reader.read_u8()? != FORMAT_VERSION {
+            return Err(ReplayError::Invalid("unsupported format version"));
+        }
+
+        let rules_version = reader.read_u32()?;
+        let mode =
+            GameMode::from_id(reader.read_str()?).ok_or(ReplayError::Invalid("unknown mode"))?;
+        let randomizer_kind = RandomizerKind::from_id(reader.read_str()?)
+            .ok_or(ReplayError::Invalid("unknown randomizer"))?;
+        let seed = reader.read_u64()?;
+
+        let mut handling = HandlingConfig::default();
+
+        for setting in HandlingSetting::ALL {
+            handling.set_raw(setting, reader.read_u32()? as u64);
+        }
+
+        let score = reader.read_u64()? as usize;
+        let rows_cleared = reader.read_u32()? as usize;
+        let is_game_over = reader.read_u8()? != 0;
+
+        let mut inputs = Vec::new();
+
+        for _ in 0..reader.read_u32()? {
+            let input = decode_input(reader.read_u16()?);
+            let count = reader.read_u32()? as usize;
+
+            if inputs.len() + count > MAX_INPUTS {
+                return Err(ReplayError::Invalid("too many inputs"));
+            }
+
+            inputs.extend(std::iter::repeat_n(input, count));
+        }
+
+        let mut checkpoints = Vec::new();
+
+        for _ in 0..reader.read_u32()? {
+            let tick = reader.read_u32()? as usize;
+            let hash = reader.read_u64()?;
+            checkpoints.push((tick, hash));
+        }
+
+        Ok(Self {
+            rules_version,
+            mode,
+            randomizer_kind,
+            seed,
+            handling,
+            score,
+            rows_cleared,
+            is_game_over,
+            inputs,
+            checkpoints,
+        })
+    }
+
+    /// Save the replay to the replays directory, named after the current time and the mode.
+    pub fn save(&self) -> Result<PathBuf, ReplayError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or(0);
+
+        fs::create_dir_all(REPLAYS_DIR)?;
+
+        let path = Path::new(REPLAYS_DIR).join(format!(
+            "{}-{}.{}",
+            timestamp,
+            self.mode.get_id(),
+            REPLAY_EXTENSION
+        ));
+
+        fs::write(&path, self.to_bytes())?;
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// List saved replays, newest first.
+    pub fn list_saved() -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(REPLAYS_DIR)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.extension()
+                            .is_some_and(|extension| extension == REPLAY_EXTENSION)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // File names start with a timestamp, so sorting by name sorts by age.
+        paths.sort();
+        paths.reverse();
+        paths
+    }
+
+    /// Get the time the game lasted, in milliseconds.
+    pub fn get_duration_ms(&self) -> u64 {
+        (self.inputs.len() as f64 * 1000.0 / crate::game_state::TICKS_PER_SECOND as f64).round()
+            as u64
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaybackSpeed {
+    Normal,
+    Fast,
+    /// Advance one tick at a time, on demand.
+    FrameStep,
+}
+
+impl PlaybackSpeed {
+    pub const ALL: [PlaybackSpeed; 3] = [
+        PlaybackSpeed::Normal,
+        PlaybackSpeed::Fast,
+        PlaybackSpeed::FrameStep,
+    ];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PlaybackSpeed::Normal => "Normal",
+            PlaybackSpeed::Fast => "Fast",
+            PlaybackSpeed::FrameStep => "Step",
+        }
+    }
+
+    /// Number of ticks to play back per tick of real time.
+    pub fn get_ticks_per_tick(&self) -> usize {
+        match self {
+            PlaybackSpeed::Normal => 1,
+            PlaybackSpeed::Fast => 4,
+            PlaybackSpeed::FrameStep => 0,
+        }
+    }
+
+    /// Get the next speed, wrapping around.
+    pub fn cycle(&self, forward: bool) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|speed| speed == self)
+            .unwrap_or(0);
+        let offset = if forward { 1 } else { Self::ALL.len() - 1 };
+
+        Self::ALL[(index + offset) % Self::ALL.len()]
+    }
+}
+
+/// Plays a replay back by feeding its inputs into a fresh game, checking the game state against the recorded
+/// checkpoints as it goes.
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub game_state: GameState,
+    pub speed: PlaybackSpeed,
+    input_index: usize,
+    checkpoint_index: usize,
+    // The tick at which playback first diverged from the recorded game, if it has
+    divergence_tick: Option<usize>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let game_state = GameState::new(
+            replay.mode,
+            replay.randomizer_kind,
+            replay.seed,
+            replay.handling,
+        );
+
+        Self {
+            replay,
+            game_state,
+            speed: PlaybackSpeed::Normal,
+            input_index: 0,
+            checkpoint_index: 0,
+            divergence_tick: None,
+        }
+    }
+
+    pub fn get_is_finished(&self) -> bool {
+        self.input_index >= self.replay.inputs.len() || self.game_state.get_is_game_over()
+    }
+
+    /// Advance playback by one tick.
+    pub fn step(&mut self) {
+        if self.get_is_finished() {
+            return;
+        }
+
+        self.game_state.step(self.replay.inputs[self.input_index]);
+        self.input_index += 1;
+
+        let tick = self.game_state.get_tick();
+
+        while let Some(&(checkpoint_tick, hash)) =
+            self.replay.checkpoints.get(self.checkpoint_index)
+        {
+            if checkpoint_tick > tick {
+                break;
+            }
+
+            self.checkpoint_index += 1;
+
+            if checkpoint_tick == tick
+                && self.divergence_tick.is_none()
+                && hash != self.game_state.get_state_hash()
+            {
+                self.divergence_tick = Some(tick);
+            }
+        }
+
+        // If the recorded game ended, it did so when its inputs ran out, so playback should end at the same time.
+        // A game that was left part way through shouldn't end at all.
+        let has_ended_differently = self.game_state.get_is_game_over()
+            != (self.is_last_input() && self.replay.is_game_over);
+
+        if has_ended_differently && self.divergence_tick.is_none() {
+            self.divergence_tick = Some(tick);
+        }
+    }
+
+    fn is_last_input(&self) -> bool {
+        self.input_index >= self.replay.inputs.len()
+    }
+
+    pub fn get_divergence_tick(&self) -> Option<usize> {
+        self.divergence_tick
+    }
+
+    /// Whether the replay was recorded under different rules than the current ones.
+    pub fn get_is_rules_mismatch(&self) -> bool {
+        self.replay.rules_version != RULES_VERSION
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpinKind {
+    None,
+    Mini,
+    Full,
+}
+
+/// The kind of line clear (or T-spin) that resulted from locking a piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClearKind {
+    Single,
+    Double,
+    Triple,
+    Tetris,
+    /// A mini T-spin, clearing the given number of rows (0-2).
+    TSpinMini(usize),
+    /// A full T-spin, clearing the given number of rows (0-3).
+    TSpin(usize),
+}
+
+impl ClearKind {
+    /// Get the kind of clear for the given spin and number of rows cleared, if it's worth anything.
+    pub fn from_rows_cleared(rows_cleared: usize, spin: SpinKind) -> Option<Self> {
+        match (spin, rows_cleared) {
+            (SpinKind::Mini, rows) => Some(ClearKind::TSpinMini(rows)),
+            (SpinKind::Full, rows) => Some(ClearKind::TSpin(rows)),
+            (SpinKind::None, 1) => Some(ClearKind::Single),
+            (SpinKind::None, 2) => Some(ClearKind::Double),
+            (SpinKind::None, 3) => Some(ClearKind::Triple),
+            (SpinKind::None, 4) => Some(ClearKind::Tetris),
+            _ => None,
+        }
+    }
+
+    pub fn get_rows_cleared(&self) -> usize {
+        match self {
+            ClearKind::Single => 1,
+            ClearKind::Double => 2,
+            ClearKind::Triple => 3,
+            ClearKind::Tetris => 4,
+            ClearKind::TSpinMini(rows) | ClearKind::TSpin(rows) => *rows,
+        }
+    }
+
+    /// Whether this is a "difficult" clear, which starts or continues a back-to-back chain: a Tetris, or
+    /// any T-spin that clears at least one row.
+    pub fn is_difficult(&self) -> bool {
+        match self {
+            ClearKind::Tetris => true,
+            ClearKind::TSpinMini(rows) | ClearKind::TSpin(rows) => *rows > 0,
+            _ => false,
+        }
+    }
+
+    /// Get the base number of points awarded for this kind of clear, per the Guideline. This should be
+    /// multiplied by the current level.
+    pub fn get_points(&self) -> usize {
+        match self {
+            ClearKind::Single => 100,
+            ClearKind::Double => 300,
+            ClearKind::Triple => 500,
+            ClearKind::Tetris => 800,
+            ClearKind::TSpinMini(0) => 100,
+            ClearKind::TSpinMini(1) => 200,
+            ClearKind::TSpinMini(_) => 400,
+            ClearKind::TSpin(0) => 400,
+            ClearKind::TSpin(1) => 800,
+            ClearKind::TSpin(2) => 1200,
+            ClearKind::TSpin(_) => 1600,
+        }
+    }
+}
+
+/// Get the base number of bonus points awarded for a perfect clear (clearing every block from the
+/// playfield), per the Guideline. Like line clear points, this should be multiplied by the current level.
+pub fn get_perfect_clear_points(rows_cleared: usize, is_back_to_back_tetris: bool) -> usize {
+    match rows_cleared {
+        1 => 800,
+        2 => 1200,
+        3 => 1800,
+        4 if is_back_to_back_tetris => 3200,
+        4 => 2000,
+        _ => 0,
+    }
+}
+
+impl Display for ClearKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows_label = match self.get_rows_cleared() {
+            0 => "",
+            1 => " SINGLE",
+            2 => " DOUBLE",
+            _ => " TRIPLE",
+        };
+
+        match self {
+            ClearKind::Single => write!(f, "SINGLE"),
+            ClearKind::Double => write!(f, "DOUBLE"),
+            ClearKind::Triple => write!(f, "TRIPLE"),
+            ClearKind::Tetris => write!(f, "TETRIS"),
+            ClearKind::TSpinMini(_) => write!(f, "MINI T-SPIN{}", rows_label),
+            ClearKind::TSpin(_) => write!(f, "T-SPIN{}", rows_label),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Stats collected over a game, for the stats panel and the game over screen, and for exporting as JSON.
+
+use crate::game_state::{GameInput, GameState};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const STATS_DIR: &str = "./stats";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GameStats {
+    pub pieces_placed: usize,
+    /// Presses of any game key. Holding a key down only counts as one press.
+    pub keys_pressed: usize,
+    pub singles: usize,
+    pub doubles: usize,
+    pub triples: usize,
+    pub tetrises: usize,
+    /// T-spins of any kind, including minis and those that don't clear any rows
+    pub t_spins: usize,
+    pub perfect_clears: usize,
+    pub max_combo: usize,
+    /// Garbage rows sent, counting those that went to cancelling incoming garbage
+    pub attack_sent: usize,
+}
+
+impl GameStats {
+    /// Count the keys pressed for an input, given the input on the tick before.
+    pub fn add_keys_pressed(&mut self, input: GameInput, last_input: GameInput) {
+        self.keys_pressed += [
+            input.soft_drop && !last_input.soft_drop,
+            input.shift_left && !last_input.shift_left,
+            input.shift_right && !last_input.shift_right,
+            input.rotate_right,
+            input.rotate_left,
+            input.rotate_180,
+            input.hard_drop,
+            input.hold_piece,
+        ]
+        .iter()
+        .filter(|&&is_pressed| is_pressed)
+        .count();
+    }
+
+    /// Get pieces placed per second, over the given play time.
+    pub fn get_pps(&self, elapsed_ms: u64) -> f32 {
+        if elapsed_ms == 0 {
+            return 0.0;
+        }
+
+        self.pieces_placed as f32 / (elapsed_ms as f32 / 1000.0)
+    }
+
+    /// Get keys pressed per piece placed.
+    pub fn get_kpp(&self) -> f32 {
+        if self.pieces_placed == 0 {
+            return 0.0;
+        }
+
+        self.keys_pressed as f32 / self.pieces_placed as f32
+    }
+
+    /// Get attack sent per minute, over the given play time.
+    pub fn get_apm(&self, elapsed_ms: u64) -> f32 {
+        if elapsed_ms == 0 {
+            return 0.0;
+        }
+
+        self.attack_sent as f32 / (elapsed_ms as f32 / 60_000.0)
+    }
+}
+
+/// A game's stats as they're exported, along with enough about the game to tell which one they came from.
+#[derive(Serialize)]
+struct StatsExport {
+    mode: &'static str,
+    randomizer: &'static str,
+    seed: u64,
+    score: usize,
+    lines: usize,
+    level: usize,
+    duration_ms: u64,
+    pps: f32,
+    kpp: f32,
+    apm: f32,
+    #[serde(flatten)]
+    stats: GameStats,
+}
+
+/// Get a game's stats as pretty printed JSON.
+pub fn to_json(game_state: &GameState) -> String {
+    let stats = game_state.get_stats();
+    let elapsed_ms = game_state.get_elapsed_ms();
+
+    let export = StatsExport {
+        mode: game_state.get_mode().get_id(),
+        randomizer: game_state.get_randomizer_kind().get_id(),
+        seed: game_state.get_seed(),
+        score: game_state.get_score(),
+        lines: game_state.get_rows_cleared(),
+        level: game_state
+            .get_master_level()
+            .unwrap_or(game_state.get_level()),
+        duration_ms: elapsed_ms,
+        pps: stats.get_pps(elapsed_ms),
+        kpp: stats.get_kpp(),
+        apm: stats.get_apm(elapsed_ms),
+        stats,
+    };
+
+    // Every field is a plain number or string, so this can't fail.
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+/// Save a game's stats as JSON in the stats directory, returning the path they were saved to.
+pub fn save_json(game_state: &GameState) -> std::io::Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+
+    fs::create_dir_all(STATS_DIR)?;
+
+    let path = Path::new(STATS_DIR).join(format!(
+        "{}-{}.json",
+        timestamp,
+        game_state.get_mode().get_id()
+    ));
+
+    fs::write(&path, to_json(game_state))?;
+    Ok(path)
+}
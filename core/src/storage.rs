@@ -0,0 +1,35 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+/// Read records stored one per line, as "key=value". Malformed lines are ignored.
+pub fn read_records(path: &str) -> HashMap<String, u64> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.to_string(), value.parse::<u64>().ok()?))
+        })
+        .collect()
+}
+
+/// Write records one per line, as "key=value", sorted by key.
+pub fn write_records(path: &str, records: &HashMap<String, u64>) -> io::Result<()> {
+    let mut lines: Vec<String> = records
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    lines.sort();
+
+    write_atomic(path, lines.join("\n"))
+}
+
+/// Write a file by writing to a temporary file next to it and then renaming that into place, so a crash part
+/// way through never leaves the file half written.
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)
+}
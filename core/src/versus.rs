@@ -0,0 +1,90 @@
+use crate::attack::AttackTable;
+use crate::game_mode::GameMode;
+use crate::game_state::{GameInput, GameState};
+use crate::handling::HandlingConfig;
+use crate::randomizer::RandomizerKind;
+
+pub const PLAYER_COUNT: usize = 2;
+
+/// A versus match between two players. Both players get the same pieces, and line clears send garbage to the
+/// other player. The first player to top out loses.
+pub struct Versus {
+    pub players: [GameState; PLAYER_COUNT],
+    is_over: bool,
+    // The index of the winning player, once the match is over. None if both players topped out on the same tick.
+    winner: Option<usize>,
+}
+
+impl Versus {
+    pub fn new(
+        randomizer_kind: RandomizerKind,
+        seed: u64,
+        handlings: [HandlingConfig; PLAYER_COUNT],
+        attack_table: AttackTable,
+    ) -> Self {
+        // Each player plays with their own handling, which doesn't affect what the other player sees.
+        let players = handlings.map(|handling| {
+            let mut game_state =
+                GameState::new(GameMode::Marathon, randomizer_kind, seed, handling);
+            game_state.set_attack_table(attack_table);
+            game_state
+        });
+
+        Self {
+            players,
+            is_over: false,
+            winner: None,
+        }
+    }
+
+    /// Advance both players by one tick, then pass the garbage each of them sent on to the other.
+    pub fn step(&mut self, inputs: [GameInput; PLAYER_COUNT]) {
+        if self.is_over {
+            return;
+        }
+
+        for (game_state, input) in self.players.iter_mut().zip(inputs) {
+            game_state.step(input);
+        }
+
+        let attacks = self
+            .players
+            .each_mut()
+            .map(|game_state| game_state.take_outgoing_garbage());
+
+        for (index, attack) in attacks.into_iter().enumerate() {
+            if attack > 0 {
+                self.players[(index + 1) % PLAYER_COUNT].receive_garbage(attack);
+            }
+        }
+
+        let topped_out = self
+            .players
+            .each_ref()
+            .map(|game_state| game_state.get_is_game_over());
+
+        if topped_out.contains(&true) {
+            self.is_over = true;
+            self.winner = topped_out.iter().position(|is_topped_out| !is_topped_out);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        for game_state in &mut self.players {
+            game_state.toggle_pause();
+        }
+    }
+
+    pub fn get_is_paused(&self) -> bool {
+        self.players[0].get_is_paused()
+    }
+
+    pub fn get_is_over(&self) -> bool {
+        self.is_over
+    }
+
+    /// Get the index of the player who won, once the match is over. None if it was a draw.
+    pub fn get_winner(&self) -> Option<usize> {
+        self.winner
+    }
+}
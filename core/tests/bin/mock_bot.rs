@@ -0,0 +1,111 @@
+//! A bot that speaks just enough of the Tetris Bot Protocol to be tested against. It suggests dropping the
+//! next piece, unrotated, wherever it lands lowest, and follows along with the game using play and new_piece.
+
+use bloxide_core::bot::{
+    from_board, from_piece_location, get_piece, to_piece_location, BotMessage, FrontendMessage,
+    Spin, TbpMove,
+};
+use bloxide_core::grid::{Grid, GRID_COUNT_COLS};
+use bloxide_core::placement::PiecePosition;
+use std::io::{self, BufRead, Write};
+
+fn send(message: &BotMessage) {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", serde_json::to_string(message).unwrap()).unwrap();
+    stdout.flush().unwrap();
+}
+
+/// Get every move for the piece that drops straight down without rotating, lowest landing first.
+fn get_moves(grid: &Grid, piece_name: &str) -> Vec<TbpMove> {
+    let Some(piece) = get_piece(piece_name) else {
+        return Vec::new();
+    };
+
+    let mut positions: Vec<PiecePosition> = (-2..GRID_COUNT_COLS as isize)
+        .map(|col| PiecePosition {
+            row: 0,
+            col,
+            orientation: 0,
+        })
+        .filter(|position| !position.collides(grid, &piece))
+        .map(|position| position.get_landing_position(grid, &piece))
+        .collect();
+
+    positions.sort_by_key(|position| -position.row);
+
+    positions
+        .into_iter()
+        .map(|position| TbpMove {
+            location: to_piece_location(&piece, position),
+            spin: Spin::None,
+        })
+        .collect()
+}
+
+fn main() {
+    send(&BotMessage::Info {
+        name: String::from("Mock Bot"),
+        version: String::from("1.0"),
+        author: String::from("bloxide"),
+        features: Vec::new(),
+    });
+
+    let mut grid = Grid::new();
+    let mut hold: Option<String> = None;
+    let mut queue: Vec<String> = Vec::new();
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        let Ok(message) = serde_json::from_str::<FrontendMessage>(&line) else {
+            eprintln!("mock-bot: couldn't parse {}", line);
+            continue;
+        };
+
+        match message {
+            FrontendMessage::Rules {} => send(&BotMessage::Ready),
+            FrontendMessage::Start {
+                hold: start_hold,
+                queue: start_queue,
+                board,
+                ..
+            } => {
+                grid = from_board(&board);
+                hold = start_hold;
+                queue = start_queue;
+            }
+            FrontendMessage::Suggest => {
+                let moves = queue
+                    .first()
+                    .map_or(Vec::new(), |piece| get_moves(&grid, piece));
+
+                send(&BotMessage::Suggestion { moves });
+            }
+            FrontendMessage::Play { tbp_move } => {
+                let location = &tbp_move.location;
+
+                if queue.first() != Some(&location.piece) {
+                    // Playing something else means holding first.
+                    let swapped_piece = queue.remove(0);
+
+                    if hold.is_none() {
+                        queue.remove(0);
+                    }
+
+                    hold = Some(swapped_piece);
+                } else {
+                    queue.remove(0);
+                }
+
+                if let Some((piece, position)) = from_piece_location(location) {
+                    position.place(&mut grid, &piece);
+                }
+            }
+            FrontendMessage::NewPiece { piece } => queue.push(piece),
+            FrontendMessage::Stop => {}
+            FrontendMessage::Quit => break,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use bloxide_core::bot::{
+    from_piece_location, to_piece_location, Bot, BotPlayer, FrontendMessage, Orientation,
+    PieceLocation, Spin, TbpMove,
+};
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::GameState;
+use bloxide_core::grid::{Grid, GRID_COUNT_COLS, GRID_COUNT_ROWS};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::piece::pieces;
+use bloxide_core::placement::PiecePosition;
+use bloxide_core::randomizer::RandomizerKind;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MOCK_BOT: &str = env!("CARGO_BIN_EXE_mock-bot");
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test]
+fn messages_use_the_protocol_format() {
+    let message = FrontendMessage::Play {
+        tbp_move: TbpMove {
+            location: PieceLocation {
+                piece: String::from("T"),
+                orientation: Orientation::North,
+                x: 4,
+                y: 0,
+            },
+            spin: Spin::None,
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_string(&message).unwrap(),
+        r#"{"type":"play","move":{"location":{"type":"T","orientation":"north","x":4,"y":0},"spin":"none"}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<FrontendMessage>(r#"{"type":"suggest"}"#).unwrap(),
+        FrontendMessage::Suggest
+    );
+}
+
+#[test]
+fn piece_locations_round_trip() {
+    let grid = Grid::new();
+
+    for piece in pieces::ALL {
+        for orientation in 0..4 {
+            let position = PiecePosition {
+                row: 5,
+                col: 3,
+                orientation,
+            }
+            .get_landing_position(&grid, &piece);
+            let location = to_piece_location(&piece, position);
+
+            assert_eq!(
+                from_piece_location(&location).map(|(piece, position)| (piece.name, position)),
+                Some((piece.name, position)),
+                "{} facing {:?}",
+                piece.name,
+                location.orientation
+            );
+        }
+    }
+
+    // A T piece lying flat on the floor has its center in the bottom row.
+    let position = PiecePosition {
+        row: 0,
+        col: 3,
+        orientation: 0,
+    }
+    .get_landing_position(&grid, &pieces::T);
+    let location = to_piece_location(&pieces::T, position);
+
+    assert_eq!((location.x, location.y), (4, 0));
+}
+
+#[test]
+fn bot_plays_a_game() {
+    let bot = Bot::spawn(MOCK_BOT, &[]).unwrap();
+    assert_eq!(bot.get_name(), "Mock Bot");
+
+    let mut bot_player = BotPlayer::new(bot);
+    let mut game_state = GameState::new(
+        GameMode::Marathon,
+        RandomizerKind::SevenBag,
+        1234,
+        HandlingConfig::default(),
+    );
+    let started_at = Instant::now();
+    let mut pieces_placed = 0;
+
+    while pieces_placed < 20 && !game_state.get_is_game_over() {
+        assert!(started_at.elapsed() < TIMEOUT, "bot stopped playing");
+
+        let input = bot_player.get_input(&game_state);
+        assert!(
+            bot_player.get_error().is_none(),
+            "{:?}",
+            bot_player.get_error()
+        );
+
+        if bot_player.get_is_thinking() {
+            thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+
+        if input.hard_drop {
+            pieces_placed += 1;
+        }
+
+        game_state.step(input);
+    }
+
+    assert_eq!(pieces_placed, 20);
+    assert!(!game_state.get_is_game_over());
+    // Every block placed is on the board, apart from the rows that were cleared, and no filled rows are left
+    // behind.
+    let grid = game_state.get_grid_locked();
+    let block_count = (0..GRID_COUNT_ROWS)
+        .flat_map(|row_id| (0..GRID_COUNT_COLS).map(move |col_id| (row_id, col_id)))
+        .filter(|&(row_id, col_id)| grid.has_block_at_cell(row_id, col_id))
+        .count();
+
+    assert!(block_count > 0);
+    assert_eq!(
+        block_count,
+        20 * 4 - game_state.get_rows_cleared() * GRID_COUNT_COLS
+    );
+    assert!(grid.get_filled_rows().is_empty());
+}
+
+#[test]
+fn bot_starts_in_the_background() {
+    let bot_start = Bot::spawn_in_background(String::from(MOCK_BOT), Vec::new());
+    let started_at = Instant::now();
+
+    let bot = loop {
+        assert!(started_at.elapsed() < TIMEOUT, "bot never started");
+
+        if let Some(bot) = bot_start.poll().unwrap() {
+            break bot;
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    assert_eq!(bot.get_name(), "Mock Bot");
+}
@@ -0,0 +1,73 @@
+//! Helpers for setting up games in the integration tests.
+
+use bloxide_core::attack::AttackTable;
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::GameState;
+use bloxide_core::grid::GRID_COUNT_COLS;
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::placement::{find_placements, plan_inputs, PiecePosition};
+use bloxide_core::randomizer::RandomizerKind;
+
+pub const TETRIS_ROWS: usize = 4;
+
+/// Find the first resting position for the active piece whose cells pass the given check.
+pub fn find_target(
+    game_state: &GameState,
+    is_wanted: impl Fn(&[(isize, isize)]) -> bool,
+) -> PiecePosition {
+    let piece = game_state.get_active_piece();
+
+    find_placements(
+        game_state.get_grid_locked(),
+        &piece,
+        game_state.get_active_piece_position(),
+    )
+    .into_iter()
+    .find(|position| is_wanted(&position.get_cells(&piece)))
+    .unwrap()
+}
+
+/// Move the active piece into the first resting position whose cells pass the given check, and hard drop it.
+pub fn place_piece(game_state: &mut GameState, is_wanted: impl Fn(&[(isize, isize)]) -> bool) {
+    let target = find_target(game_state, is_wanted);
+
+    for input in plan_inputs(game_state, target, false).unwrap() {
+        game_state.step(input);
+    }
+}
+
+pub fn is_in_first_column(cells: &[(isize, isize)]) -> bool {
+    cells.iter().all(|&(_, col)| col == 0)
+}
+
+/// Start a game whose second piece is an I, with four rows of garbage under the first piece that only need an
+/// I in the leftmost column to clear.
+pub fn new_game_ready_for_tetris() -> GameState {
+    let mut game_state = (0..)
+        .map(|seed| {
+            GameState::new(
+                GameMode::Marathon,
+                RandomizerKind::SevenBag,
+                seed,
+                HandlingConfig::default(),
+            )
+        })
+        .find(|game_state| {
+            game_state.get_active_piece().name != "I"
+                && game_state.get_piece_previews()[0].name == "I"
+        })
+        .unwrap();
+
+    game_state.set_attack_table(AttackTable::default());
+    game_state.queue_garbage(TETRIS_ROWS, 0);
+
+    // The first piece goes against the right wall, out of the I's way. The garbage goes in underneath it.
+    place_piece(&mut game_state, |cells| {
+        cells
+            .iter()
+            .any(|&(_, col)| col == GRID_COUNT_COLS as isize - 1)
+    });
+
+    assert_eq!(game_state.get_active_piece().name, "I");
+    game_state
+}
@@ -0,0 +1,51 @@
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+
+const TICK_COUNT: usize = 60 * 60;
+
+fn new_game(seed: u64) -> GameState {
+    GameState::new(
+        GameMode::Marathon,
+        RandomizerKind::SevenBag,
+        seed,
+        HandlingConfig::default(),
+    )
+}
+
+fn get_input(tick: usize) -> GameInput {
+    GameInput {
+        shift_left: tick % 7 < 2,
+        shift_right: tick % 11 < 3,
+        rotate_right: tick.is_multiple_of(13),
+        soft_drop: tick % 17 < 4,
+        hard_drop: tick.is_multiple_of(29),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn same_seed_and_inputs_give_the_same_game() {
+    let mut game_state = new_game(1234);
+    let mut same_game_state = new_game(1234);
+
+    for tick in 0..TICK_COUNT {
+        if game_state.get_is_game_over() {
+            break;
+        }
+
+        game_state.step(get_input(tick));
+        same_game_state.step(get_input(tick));
+
+        assert_eq!(
+            game_state.get_state_hash(),
+            same_game_state.get_state_hash(),
+            "tick {}",
+            tick
+        );
+    }
+
+    assert!(game_state.get_stats().pieces_placed > 0);
+    assert_eq!(game_state.get_score(), same_game_state.get_score());
+}
@@ -0,0 +1,38 @@
+use bloxide_core::finesse::{get_min_inputs, get_spawn_position};
+use bloxide_core::grid::Grid;
+use bloxide_core::piece::{pieces, Piece};
+use bloxide_core::placement::{find_placements, PiecePosition};
+
+fn get_piece(name: &str) -> Piece {
+    *pieces::ALL.iter().find(|piece| piece.name == name).unwrap()
+}
+
+/// Get the fewest inputs for each flat placement of a piece (in its spawn orientation) on an empty grid,
+/// ordered from the leftmost column to the rightmost.
+fn get_min_inputs_by_col(piece: &Piece) -> Vec<usize> {
+    let mut placements: Vec<PiecePosition> =
+        find_placements(&Grid::new(), piece, get_spawn_position(piece))
+            .into_iter()
+            .filter(|position| position.orientation == 0)
+            .collect();
+
+    placements.sort_by_key(|position| position.get_cells(piece).iter().map(|&(_, col)| col).min());
+
+    placements
+        .iter()
+        .map(|&position| get_min_inputs(piece, position))
+        .collect()
+}
+
+#[test]
+fn min_inputs_match_known_finesse() {
+    // Holding a shift to the wall is one press, so the columns next to the walls take a tap back.
+    assert_eq!(
+        get_min_inputs_by_col(&get_piece("O")),
+        vec![1, 2, 2, 1, 0, 1, 2, 2, 1]
+    );
+    assert_eq!(
+        get_min_inputs_by_col(&get_piece("T")),
+        vec![1, 2, 1, 0, 1, 2, 2, 1]
+    );
+}
@@ -0,0 +1,88 @@
+use bloxide_core::block::{Block, GARBAGE_COLOR};
+use bloxide_core::game_mode::{GameMode, CHEESE_VISIBLE_ROWS};
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::grid::{Grid, GRID_COUNT_COLS, GRID_COUNT_ROWS};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+
+/// Get the columns of the empty cells in a row.
+fn get_holes(grid: &Grid, row_id: usize) -> Vec<usize> {
+    (0..GRID_COUNT_COLS)
+        .filter(|&col_id| !grid.has_block_at_cell(row_id, col_id))
+        .collect()
+}
+
+fn new_game(mode: GameMode, seed: u64) -> GameState {
+    GameState::new(
+        mode,
+        RandomizerKind::SevenBag,
+        seed,
+        HandlingConfig::default(),
+    )
+}
+
+#[test]
+fn garbage_pushes_the_stack_up_with_one_hole_per_row() {
+    let mut grid = Grid::new();
+    grid.set_cell(GRID_COUNT_ROWS - 1, 5, Some(Block::new(GARBAGE_COLOR)));
+
+    assert!(!grid.insert_garbage(3, 2));
+
+    assert!(grid.has_block_at_cell(GRID_COUNT_ROWS - 4, 5));
+
+    for row_id in GRID_COUNT_ROWS - 3..GRID_COUNT_ROWS {
+        assert!(grid.is_garbage_row(row_id));
+        assert_eq!(get_holes(&grid, row_id), vec![2], "row {}", row_id);
+    }
+}
+
+#[test]
+fn cheese_holes_come_from_the_seed() {
+    let game_state = new_game(GameMode::Cheese(18), 1234);
+    let same_seed_game_state = new_game(GameMode::Cheese(18), 1234);
+
+    for row_id in GRID_COUNT_ROWS - CHEESE_VISIBLE_ROWS..GRID_COUNT_ROWS {
+        let holes = get_holes(game_state.get_grid_locked(), row_id);
+
+        assert_eq!(holes.len(), 1, "row {}", row_id);
+        assert_eq!(
+            holes,
+            get_holes(same_seed_game_state.get_grid_locked(), row_id)
+        );
+    }
+
+    assert!(game_state
+        .get_grid_locked()
+        .is_row_empty(GRID_COUNT_ROWS - CHEESE_VISIBLE_ROWS - 1));
+}
+
+#[test]
+fn garbage_pushed_above_the_ceiling_tops_out() {
+    let mut game_state = new_game(GameMode::Marathon, 1234);
+
+    // The piece locks on the floor, so pushing the whole stack up by the height of the grid less one row
+    // pushes it off the top.
+    game_state.queue_garbage(GRID_COUNT_ROWS - 1, 0);
+    game_state.step(GameInput {
+        hard_drop: true,
+        ..Default::default()
+    });
+
+    assert!(game_state.get_is_game_over());
+}
+
+#[test]
+fn garbage_below_the_ceiling_doesnt_top_out() {
+    let mut game_state = new_game(GameMode::Marathon, 1234);
+
+    game_state.queue_garbage(4, 0);
+    game_state.step(GameInput {
+        hard_drop: true,
+        ..Default::default()
+    });
+
+    assert!(!game_state.get_is_game_over());
+    assert!(game_state
+        .get_grid_locked()
+        .is_garbage_row(GRID_COUNT_ROWS - 1));
+}
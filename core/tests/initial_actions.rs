@@ -0,0 +1,109 @@
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+
+// Far more ticks than the delays between pieces should ever take.
+const MAX_TICKS: usize = 60 * 60;
+const ARE_TICKS: isize = 10;
+
+fn new_game(mode: GameMode, are_ticks: isize) -> GameState {
+    GameState::new(
+        mode,
+        RandomizerKind::SevenBag,
+        0,
+        HandlingConfig {
+            are_ticks,
+            ..Default::default()
+        },
+    )
+}
+
+/// Hard drop the first piece while holding the given keys, and keep holding them until the next piece spawns.
+fn hard_drop_holding(game_state: &mut GameState, held_input: GameInput) {
+    game_state.step(GameInput {
+        hard_drop: true,
+        ..held_input
+    });
+
+    for _ in 0..MAX_TICKS {
+        if game_state.get_is_piece_active() {
+            break;
+        }
+
+        game_state.step(held_input);
+    }
+
+    assert!(game_state.get_is_piece_active());
+}
+
+fn get_second_piece_name(mode: GameMode) -> &'static str {
+    new_game(mode, ARE_TICKS).get_piece_previews()[0].name
+}
+
+#[test]
+fn hold_key_held_through_the_entry_delay_holds_the_next_piece() {
+    let mut game_state = new_game(GameMode::Marathon, ARE_TICKS);
+
+    hard_drop_holding(
+        &mut game_state,
+        GameInput {
+            hold_piece_held: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        game_state.get_held_piece().map(|piece| piece.name),
+        Some(get_second_piece_name(GameMode::Marathon))
+    );
+}
+
+#[test]
+fn rotate_key_held_through_the_entry_delay_rotates_the_next_piece() {
+    let mut game_state = new_game(GameMode::Marathon, ARE_TICKS);
+
+    hard_drop_holding(
+        &mut game_state,
+        GameInput {
+            rotate_right_held: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(game_state.get_active_piece_position().orientation, 1);
+}
+
+#[test]
+fn keys_held_without_an_entry_delay_are_ignored() {
+    let mut game_state = new_game(GameMode::Marathon, 0);
+
+    hard_drop_holding(
+        &mut game_state,
+        GameInput {
+            rotate_right_held: true,
+            hold_piece_held: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(game_state.get_held_piece().is_none());
+    assert_eq!(game_state.get_active_piece_position().orientation, 0);
+}
+
+#[test]
+fn keys_held_are_ignored_without_initial_actions() {
+    let mut game_state = new_game(GameMode::Sprint, ARE_TICKS);
+
+    hard_drop_holding(
+        &mut game_state,
+        GameInput {
+            rotate_right_held: true,
+            hold_piece_held: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(game_state.get_held_piece().is_none());
+    assert_eq!(game_state.get_active_piece_position().orientation, 0);
+}
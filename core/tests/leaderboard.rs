@@ -0,0 +1,139 @@
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::leaderboard::{
+    Leaderboard, LeaderboardEntry, LeaderboardError, LEADERBOARD_SIZE,
+};
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+fn new_entry(name: &str, score: u64, time_ms: u64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        name: name.to_string(),
+        score,
+        time_ms,
+        lines: 40,
+        level: 1,
+        date: None,
+        seed: Some(0),
+    }
+}
+
+fn get_names(leaderboard: &Leaderboard, mode: GameMode) -> Vec<&str> {
+    leaderboard
+        .get_entries(mode)
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect()
+}
+
+/// Get an empty directory of its own for a test to keep its leaderboard in.
+fn get_test_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bloxide-{}-{}", test_name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn entries_rank_best_first_with_ties_behind() {
+    let mut leaderboard = Leaderboard::default();
+
+    assert_eq!(
+        leaderboard.add_entry(GameMode::Marathon, new_entry("a", 500, 0)),
+        Some(0)
+    );
+    assert_eq!(
+        leaderboard.add_entry(GameMode::Marathon, new_entry("b", 900, 0)),
+        Some(0)
+    );
+    assert_eq!(
+        leaderboard.add_entry(GameMode::Marathon, new_entry("c", 500, 0)),
+        Some(2)
+    );
+    assert_eq!(
+        leaderboard.add_entry(GameMode::Marathon, new_entry("d", 700, 0)),
+        Some(1)
+    );
+
+    assert_eq!(
+        get_names(&leaderboard, GameMode::Marathon),
+        vec!["b", "d", "a", "c"]
+    );
+}
+
+#[test]
+fn timed_modes_rank_the_fastest_first() {
+    let mut leaderboard = Leaderboard::default();
+
+    leaderboard.add_entry(GameMode::Sprint, new_entry("slow", 900, 90_000));
+    leaderboard.add_entry(GameMode::Sprint, new_entry("fast", 100, 30_000));
+    leaderboard.add_entry(GameMode::Sprint, new_entry("tied", 500, 30_000));
+
+    assert_eq!(
+        get_names(&leaderboard, GameMode::Sprint),
+        vec!["fast", "tied", "slow"]
+    );
+    assert!(leaderboard.get_entries(GameMode::Marathon).is_empty());
+}
+
+#[test]
+fn only_the_best_entries_are_kept() {
+    let mut leaderboard = Leaderboard::default();
+
+    for score in 0..LEADERBOARD_SIZE as u64 * 2 {
+        leaderboard.add_entry(GameMode::Marathon, new_entry("a", score, 0));
+    }
+
+    let entries = leaderboard.get_entries(GameMode::Marathon);
+    assert_eq!(entries.len(), LEADERBOARD_SIZE);
+    assert_eq!(entries[LEADERBOARD_SIZE - 1].score, LEADERBOARD_SIZE as u64);
+
+    let worse_entry = new_entry("b", LEADERBOARD_SIZE as u64, 0);
+    assert_eq!(leaderboard.get_rank(GameMode::Marathon, &worse_entry), None);
+    assert_eq!(leaderboard.add_entry(GameMode::Marathon, worse_entry), None);
+    assert_eq!(
+        leaderboard.get_entries(GameMode::Marathon).len(),
+        LEADERBOARD_SIZE
+    );
+}
+
+#[test]
+fn saved_leaderboard_loads_back() {
+    let dir = get_test_dir("round-trip");
+    let path = dir.join("leaderboard.json");
+    let mut leaderboard = Leaderboard::default();
+    leaderboard.add_entry(GameMode::Sprint, new_entry("a", 100, 30_000));
+
+    leaderboard.save_to(&path).unwrap();
+
+    let loaded_leaderboard = Leaderboard::load_from(&path).unwrap();
+    assert_eq!(
+        loaded_leaderboard.get_entries(GameMode::Sprint),
+        leaderboard.get_entries(GameMode::Sprint)
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrupt_file_is_backed_up_rather_than_overwritten() {
+    let dir = get_test_dir("corrupt");
+    let path = dir.join("leaderboard.json");
+    fs::write(&path, "{ not json").unwrap();
+
+    assert!(matches!(
+        Leaderboard::load_from(&path),
+        Err(LeaderboardError::Invalid(_))
+    ));
+
+    let backup_path = Leaderboard::back_up(&path).unwrap();
+    assert_eq!(backup_path.parent(), Some(dir.as_path()));
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), "{ not json");
+
+    // The backup survives a fresh leaderboard being saved in its place.
+    Leaderboard::default().save_to(&path).unwrap();
+    assert!(Leaderboard::load_from(&path).is_ok());
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), "{ not json");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,92 @@
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+
+// Far more ticks than a piece should ever be able to stay active for with the default handling.
+const MAX_TICKS: usize = 60 * 60;
+
+fn new_game_starting_with(piece_name: &str) -> GameState {
+    (0..)
+        .map(|seed| {
+            GameState::new(
+                GameMode::Marathon,
+                RandomizerKind::SevenBag,
+                seed,
+                HandlingConfig::default(),
+            )
+        })
+        .find(|game_state| game_state.get_active_piece().name == piece_name)
+        .unwrap()
+}
+
+/// Feed inputs into the game until the first piece locks, returning the number of ticks it took, or None if it
+/// never did.
+fn ticks_until_first_lock(
+    game_state: &mut GameState,
+    get_input: impl Fn(usize) -> GameInput,
+) -> Option<usize> {
+    for tick in 0..MAX_TICKS {
+        game_state.step(get_input(tick));
+
+        if game_state.get_stats().pieces_placed > 0 {
+            return Some(tick);
+        }
+    }
+
+    None
+}
+
+#[test]
+fn spinning_in_place_on_the_floor_eventually_locks() {
+    let mut game_state = new_game_starting_with("T");
+
+    // Drop to the floor, then spin back and forth forever. Rotating out of the flat orientation kicks the T
+    // upward, and it falls back down to the same row.
+    let ticks = ticks_until_first_lock(&mut game_state, |tick| GameInput {
+        soft_drop: tick < 60,
+        rotate_right: tick >= 60 && tick % 2 == 0,
+        rotate_left: tick >= 60 && tick % 2 == 1,
+        ..Default::default()
+    });
+
+    assert!(ticks.is_some(), "the T piece never locked");
+}
+
+#[test]
+fn shifting_back_and_forth_on_the_floor_eventually_locks() {
+    let mut game_state = new_game_starting_with("O");
+
+    let ticks = ticks_until_first_lock(&mut game_state, |tick| GameInput {
+        soft_drop: tick < 60,
+        shift_left: tick >= 60 && tick % 2 == 0,
+        shift_right: tick >= 60 && tick % 2 == 1,
+        ..Default::default()
+    });
+
+    assert!(ticks.is_some(), "the O piece never locked");
+}
+
+#[test]
+fn descending_refills_the_move_budget() {
+    let handling = HandlingConfig::default();
+    let mut game_state = new_game_starting_with("T");
+
+    // Spending the whole move budget at the top of the stack shouldn't stop the piece from resetting the lock
+    // delay once it reaches the floor.
+    for tick in 0..(handling.reset_moves as usize * 2) {
+        game_state.step(GameInput {
+            rotate_right: tick % 2 == 0,
+            ..Default::default()
+        });
+    }
+
+    let ticks = ticks_until_first_lock(&mut game_state, |tick| GameInput {
+        soft_drop: tick < 60,
+        rotate_right: tick >= 60 && tick % 20 == 0,
+        ..Default::default()
+    });
+
+    let ticks = ticks.expect("the T piece never locked");
+    assert!(ticks > 60 + handling.lock_delay_ticks as usize);
+}
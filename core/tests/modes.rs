@@ -0,0 +1,118 @@
+use bloxide_core::ai::AiPlayer;
+use bloxide_core::game_mode::{get_master_gravity, GameMode, SPRINT_ROWS, ULTRA_TICKS};
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+
+// Far more ticks than any of the goals should take the AI to reach.
+const MAX_TICKS: usize = 60 * 60 * 60;
+
+fn new_game(mode: GameMode) -> GameState {
+    GameState::new(
+        mode,
+        RandomizerKind::SevenBag,
+        1234,
+        HandlingConfig::default(),
+    )
+}
+
+/// Let the AI play until the game ends or the given check passes.
+fn play_until(game_state: &mut GameState, is_done: impl Fn(&GameState) -> bool) {
+    let mut ai_player = AiPlayer::default();
+
+    for _ in 0..MAX_TICKS {
+        if game_state.get_is_game_over() || is_done(game_state) {
+            return;
+        }
+
+        game_state.step(ai_player.get_input(game_state));
+    }
+}
+
+#[test]
+fn sprint_ends_once_40_lines_are_cleared() {
+    let mut game_state = new_game(GameMode::Sprint);
+
+    play_until(&mut game_state, |_| false);
+
+    assert!(game_state.get_is_game_over());
+    assert!(game_state.get_is_goal_reached());
+    assert!(game_state.get_rows_cleared() >= SPRINT_ROWS);
+    // The game ends on the clear that reaches the goal, which is at most a Tetris.
+    assert!(game_state.get_rows_cleared() < SPRINT_ROWS + 4);
+}
+
+#[test]
+fn ultra_ends_once_time_is_up() {
+    let mut game_state = new_game(GameMode::Ultra);
+
+    // Left alone, pieces fall slowly enough at the first level that the stack is nowhere near the top in time.
+    for _ in 0..ULTRA_TICKS - 1 {
+        game_state.step(GameInput::default());
+    }
+
+    assert!(!game_state.get_is_game_over());
+
+    game_state.step(GameInput::default());
+
+    assert!(game_state.get_is_game_over());
+    assert!(game_state.get_is_goal_reached());
+    assert_eq!(game_state.get_tick(), ULTRA_TICKS);
+    assert_eq!(game_state.get_elapsed_ms(), 120_000);
+}
+
+#[test]
+fn master_level_goes_up_as_pieces_spawn_and_rows_clear() {
+    let mut game_state = new_game(GameMode::Master);
+    let mut ai_player = AiPlayer::default();
+    let mut section_stop_spawns = 0;
+
+    assert_eq!(game_state.get_master_level(), Some(0));
+
+    for _ in 0..MAX_TICKS {
+        let level = game_state.get_master_level().unwrap();
+
+        if level >= 150 {
+            break;
+        }
+
+        let rows_cleared = game_state.get_rows_cleared();
+        let was_piece_active = game_state.get_is_piece_active();
+
+        game_state.step(ai_player.get_input(&game_state));
+        assert!(!game_state.get_is_game_over());
+
+        let level_gained = game_state.get_master_level().unwrap() - level;
+
+        // Spawning pieces can't take the level past the end of a section (x99), only clearing rows can.
+        if !was_piece_active && game_state.get_is_piece_active() {
+            let is_section_stop = level % 100 == 99;
+            section_stop_spawns += is_section_stop as usize;
+            assert_eq!(level_gained, !is_section_stop as usize, "level {}", level);
+        } else {
+            assert_eq!(
+                level_gained,
+                game_state.get_rows_cleared() - rows_cleared,
+                "level {}",
+                level
+            );
+        }
+    }
+
+    assert!(game_state.get_master_level() >= Some(150));
+    assert!(section_stop_spawns > 0);
+}
+
+#[test]
+fn master_gravity_builds_up_to_20g() {
+    assert_eq!(get_master_gravity(0), 4.0 / 256.0);
+    assert_eq!(get_master_gravity(199), 144.0 / 256.0);
+    assert_eq!(get_master_gravity(200), 4.0 / 256.0);
+    assert_eq!(get_master_gravity(251), 1.0);
+    assert_eq!(get_master_gravity(499), 3.0);
+    assert_eq!(get_master_gravity(500), 20.0);
+    assert_eq!(get_master_gravity(999), 20.0);
+
+    let game_state = new_game(GameMode::Master);
+    assert_eq!(game_state.get_gravity(), get_master_gravity(0));
+}
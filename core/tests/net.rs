@@ -0,0 +1,169 @@
+use bloxide_core::attack::AttackTable;
+use bloxide_core::game_state::GameInput;
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::net::{join, DisconnectReason, NetError, NetHost, NetVersus};
+use bloxide_core::randomizer::RandomizerKind;
+use bloxide_core::replay::RULES_VERSION;
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn bind_host() -> NetHost {
+    NetHost::bind(
+        "127.0.0.1:0",
+        1234,
+        RandomizerKind::SevenBag,
+        HandlingConfig::default(),
+        AttackTable::default(),
+    )
+    .unwrap()
+}
+
+/// Start a match between a host and a player joining it over loopback.
+fn start_match() -> (NetVersus, NetVersus) {
+    let mut host = bind_host();
+    let net_join = join(host.get_local_addr().unwrap(), HandlingConfig::default());
+    let started_at = Instant::now();
+    let mut hosted = None;
+    let mut joined = None;
+
+    while hosted.is_none() || joined.is_none() {
+        assert!(started_at.elapsed() < TIMEOUT, "nobody joined");
+
+        if hosted.is_none() {
+            hosted = host.accept().unwrap();
+        }
+
+        if joined.is_none() {
+            joined = net_join.poll().unwrap();
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    (hosted.unwrap(), joined.unwrap())
+}
+
+/// Keep stepping a match with empty inputs until the condition is met.
+fn step_until(net_versus: &mut NetVersus, condition: impl Fn(&NetVersus) -> bool) {
+    let started_at = Instant::now();
+
+    while !condition(net_versus) {
+        assert!(started_at.elapsed() < TIMEOUT, "timed out");
+        net_versus.step(GameInput::default());
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Play the given number of ticks with a fixed pattern of inputs, which is different for each player, and
+/// return the state hash of both players' games.
+fn play_ticks(mut net_versus: NetVersus, ticks: usize) -> Vec<u64> {
+    let offset = net_versus.get_local_index() * 7;
+    let mut tick = 0;
+    let started_at = Instant::now();
+
+    while net_versus.versus.players[0].get_tick() < ticks && !net_versus.get_is_over() {
+        assert!(started_at.elapsed() < TIMEOUT, "timed out");
+
+        let input = GameInput {
+            shift_left: (tick + offset) % 40 < 10,
+            shift_right: (tick + offset) % 40 >= 30,
+            rotate_right: (tick + offset).is_multiple_of(25),
+            hard_drop: (tick + offset) % 40 == 20,
+            ..Default::default()
+        };
+
+        if net_versus.step(input) {
+            tick += 1;
+        } else {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    net_versus
+        .versus
+        .players
+        .iter()
+        .map(|game_state| game_state.get_state_hash())
+        .collect()
+}
+
+#[test]
+fn both_sides_stay_in_sync() {
+    let (hosted, joined) = start_match();
+
+    let joining = thread::spawn(move || play_ticks(joined, 600));
+    let host_hashes = play_ticks(hosted, 600);
+
+    assert_eq!(host_hashes, joining.join().unwrap());
+}
+
+#[test]
+fn latency_is_measured() {
+    let (mut hosted, mut joined) = start_match();
+    let started_at = Instant::now();
+
+    // Both sides keep stepping until both have a measurement, so neither stops answering the other's pings.
+    while hosted.get_latency().is_none() || joined.get_latency().is_none() {
+        assert!(started_at.elapsed() < TIMEOUT, "timed out");
+        hosted.step(GameInput::default());
+        joined.step(GameInput::default());
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn leaving_ends_the_match_for_the_opponent() {
+    let (mut hosted, mut joined) = start_match();
+
+    joined.leave();
+    step_until(&mut hosted, |net_versus| net_versus.get_is_over());
+
+    assert_eq!(hosted.get_disconnect_reason(), Some(DisconnectReason::Left));
+    assert!(!hosted.versus.get_is_over());
+}
+
+#[test]
+fn dropped_connection_ends_the_match() {
+    let (mut hosted, joined) = start_match();
+
+    drop(joined);
+    step_until(&mut hosted, |net_versus| net_versus.get_is_over());
+
+    assert_eq!(
+        hosted.get_disconnect_reason(),
+        Some(DisconnectReason::ConnectionLost)
+    );
+}
+
+#[test]
+fn mismatched_versions_are_rejected() {
+    let mut host = bind_host();
+    let addr: SocketAddr = host.get_local_addr().unwrap();
+
+    // A hello from a future version of the protocol.
+    let mut frame = vec![10, 0, 0];
+    frame.extend_from_slice(b"BLXN");
+    frame.push(u8::MAX);
+    frame.extend_from_slice(&RULES_VERSION.to_le_bytes());
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(&frame).unwrap();
+
+    let started_at = Instant::now();
+
+    let result = loop {
+        match host.accept() {
+            Ok(None) => {
+                assert!(started_at.elapsed() < TIMEOUT, "nobody joined");
+                thread::sleep(Duration::from_millis(1));
+            }
+            result => break result,
+        }
+    };
+
+    assert!(matches!(result, Err(NetError::VersionMismatch)));
+}
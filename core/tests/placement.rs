@@ -0,0 +1,83 @@
+use bloxide_core::block::{Block, GARBAGE_COLOR};
+use bloxide_core::finesse::get_spawn_position;
+use bloxide_core::grid::{Grid, GRID_COUNT_COLS, GRID_COUNT_ROWS};
+use bloxide_core::piece::{pieces, Piece};
+use bloxide_core::placement::{find_path, find_placements, PiecePosition};
+
+fn get_piece(name: &str) -> Piece {
+    *pieces::ALL.iter().find(|piece| piece.name == name).unwrap()
+}
+
+/// Build a stack with a T-spin double slot in its bottom left corner: the T has to rotate in under the
+/// overhang, pointing down into the hole in the bottom row.
+fn get_t_spin_double_grid() -> Grid {
+    let mut grid = Grid::new();
+    let block = Some(Block::new(GARBAGE_COLOR));
+
+    for col_id in 0..GRID_COUNT_COLS {
+        if col_id != 1 {
+            grid.set_cell(GRID_COUNT_ROWS - 1, col_id, block);
+        }
+
+        if col_id > 2 {
+            grid.set_cell(GRID_COUNT_ROWS - 2, col_id, block);
+            grid.set_cell(GRID_COUNT_ROWS - 3, col_id, block);
+        }
+    }
+
+    // The overhang
+    grid.set_cell(GRID_COUNT_ROWS - 3, 2, block);
+    grid
+}
+
+#[test]
+fn placements_are_reachable_resting_and_distinct() {
+    let grid = get_t_spin_double_grid();
+    let piece = get_piece("T");
+    let spawn_position = get_spawn_position(&piece);
+    let placements = find_placements(&grid, &piece, spawn_position);
+    let mut placed_cells = Vec::new();
+
+    for position in &placements {
+        assert!(!position.collides(&grid, &piece), "{:?}", position);
+        assert_eq!(position.get_landing_position(&grid, &piece), *position);
+
+        let path = find_path(&grid, &piece, spawn_position, *position).unwrap();
+        let end_position = path.iter().fold(spawn_position, |position, next_move| {
+            next_move.apply(&grid, &piece, position).unwrap()
+        });
+        assert_eq!(end_position, *position);
+
+        let mut cells = position.get_cells(&piece);
+        cells.sort();
+        assert!(!placed_cells.contains(&cells), "{:?}", position);
+        placed_cells.push(cells);
+    }
+
+    let bottom_row = GRID_COUNT_ROWS as isize - 1;
+    let t_spin_cells = vec![
+        (bottom_row - 1, 0),
+        (bottom_row - 1, 1),
+        (bottom_row - 1, 2),
+        (bottom_row, 1),
+    ];
+    assert!(placed_cells.contains(&t_spin_cells));
+
+    // The slot can't be reached by dropping the T straight down.
+    let mut dropped_position = spawn_position;
+    dropped_position.orientation = 2;
+    assert!((-2..GRID_COUNT_COLS as isize).all(|col| {
+        let position = PiecePosition {
+            col,
+            ..dropped_position
+        };
+
+        position.collides(&grid, &piece) || {
+            let mut cells = position
+                .get_landing_position(&grid, &piece)
+                .get_cells(&piece);
+            cells.sort();
+            cells != t_spin_cells
+        }
+    }));
+}
@@ -0,0 +1,46 @@
+use bloxide_core::randomizer::{RandomizerKind, MAX_PEEK};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn peeks_match_the_pieces_dealt() {
+    for kind in RandomizerKind::ALL {
+        let mut randomizer = kind.create(0);
+
+        for _ in 0..20 {
+            let peeked: Vec<&str> = (1..=MAX_PEEK)
+                .map(|offset| randomizer.peek(offset).name)
+                .collect();
+            let dealt: Vec<&str> = (0..MAX_PEEK).map(|_| randomizer.next().name).collect();
+
+            assert_eq!(peeked, dealt, "{}", kind.get_label());
+        }
+    }
+}
+
+#[test]
+fn peek_offsets_out_of_range_panic() {
+    for kind in RandomizerKind::ALL {
+        let randomizer = kind.create(0);
+
+        for offset in [0, MAX_PEEK + 1] {
+            let result = catch_unwind(AssertUnwindSafe(|| randomizer.peek(offset)));
+            assert!(result.is_err(), "{} peek({})", kind.get_label(), offset);
+        }
+    }
+}
+
+#[test]
+fn same_seed_deals_the_same_pieces() {
+    for kind in RandomizerKind::ALL {
+        let mut randomizer = kind.create(1234);
+        let mut same_randomizer = kind.create(1234);
+        let mut other_randomizer = kind.create(4321);
+
+        let pieces: Vec<&str> = (0..100).map(|_| randomizer.next().name).collect();
+        let same_pieces: Vec<&str> = (0..100).map(|_| same_randomizer.next().name).collect();
+        let other_pieces: Vec<&str> = (0..100).map(|_| other_randomizer.next().name).collect();
+
+        assert_eq!(pieces, same_pieces, "{}", kind.get_label());
+        assert_ne!(pieces, other_pieces, "{}", kind.get_label());
+    }
+}
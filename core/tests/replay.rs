@@ -0,0 +1,103 @@
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::randomizer::RandomizerKind;
+use bloxide_core::replay::{Replay, ReplayPlayer};
+
+// Far more ticks than it takes to top out by hard dropping every piece.
+const MAX_TICKS: usize = 60 * 60;
+
+fn new_game() -> GameState {
+    GameState::new(
+        GameMode::Marathon,
+        RandomizerKind::SevenBag,
+        1234,
+        HandlingConfig::default(),
+    )
+}
+
+/// Save the game's replay to bytes and load it back, then play it all back, returning the player.
+fn play_back_round_trip(game_state: &GameState) -> ReplayPlayer {
+    let replay = Replay::from_bytes(&game_state.get_replay().to_bytes()).unwrap();
+    let mut replay_player = ReplayPlayer::new(replay);
+
+    while !replay_player.get_is_finished() {
+        replay_player.step();
+    }
+
+    replay_player
+}
+
+#[test]
+fn finished_game_plays_back_without_diverging() {
+    let mut game_state = new_game();
+
+    for tick in 0..MAX_TICKS {
+        if game_state.get_is_game_over() {
+            break;
+        }
+
+        game_state.step(GameInput {
+            shift_left: tick % 3 == 0,
+            hard_drop: tick % 2 == 0,
+            ..Default::default()
+        });
+    }
+
+    assert!(game_state.get_is_game_over());
+
+    let replay_player = play_back_round_trip(&game_state);
+
+    assert!(replay_player.replay.is_game_over);
+    assert!(replay_player.game_state.get_is_game_over());
+    assert_eq!(replay_player.get_divergence_tick(), None);
+    assert_eq!(
+        replay_player.game_state.get_state_hash(),
+        game_state.get_state_hash()
+    );
+}
+
+#[test]
+fn abandoned_game_plays_back_without_diverging() {
+    let mut game_state = new_game();
+
+    for tick in 0..200 {
+        game_state.step(GameInput {
+            shift_right: tick % 5 == 0,
+            rotate_right: tick % 7 == 0,
+            hard_drop: tick % 40 == 0,
+            ..Default::default()
+        });
+    }
+
+    assert!(!game_state.get_is_game_over());
+
+    let replay_player = play_back_round_trip(&game_state);
+
+    assert!(!replay_player.replay.is_game_over);
+    assert!(!replay_player.game_state.get_is_game_over());
+    assert_eq!(replay_player.game_state.get_tick(), 200);
+    assert_eq!(replay_player.get_divergence_tick(), None);
+    assert_eq!(
+        replay_player.game_state.get_state_hash(),
+        game_state.get_state_hash()
+    );
+}
+
+#[test]
+fn replays_claiming_too_many_inputs_are_rejected() {
+    let mut game_state = new_game();
+    game_state.step(GameInput::default());
+
+    let mut replay = game_state.get_replay();
+    replay.checkpoints.clear();
+
+    // With no checkpoints, the file ends with the only input run's count, followed by the checkpoint count.
+    let mut bytes = replay.to_bytes();
+    assert!(Replay::from_bytes(&bytes).is_ok());
+
+    let count_start = bytes.len() - 8;
+    bytes[count_start..count_start + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert!(Replay::from_bytes(&bytes).is_err());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use bloxide_core::placement::plan_inputs;
+use bloxide_core::scoring::{get_perfect_clear_points, ClearKind, SpinKind};
+use common::{find_target, is_in_first_column, new_game_ready_for_tetris, TETRIS_ROWS};
+
+#[test]
+fn clears_score_per_the_guideline() {
+    // (spin, rows cleared, kind, base points, difficult)
+    let cases = [
+        (SpinKind::None, 1, ClearKind::Single, 100, false),
+        (SpinKind::None, 2, ClearKind::Double, 300, false),
+        (SpinKind::None, 3, ClearKind::Triple, 500, false),
+        (SpinKind::None, 4, ClearKind::Tetris, 800, true),
+        (SpinKind::Mini, 0, ClearKind::TSpinMini(0), 100, false),
+        (SpinKind::Mini, 1, ClearKind::TSpinMini(1), 200, true),
+        (SpinKind::Mini, 2, ClearKind::TSpinMini(2), 400, true),
+        (SpinKind::Full, 0, ClearKind::TSpin(0), 400, false),
+        (SpinKind::Full, 1, ClearKind::TSpin(1), 800, true),
+        (SpinKind::Full, 2, ClearKind::TSpin(2), 1200, true),
+        (SpinKind::Full, 3, ClearKind::TSpin(3), 1600, true),
+    ];
+
+    for (spin, rows_cleared, expected_kind, expected_points, expected_is_difficult) in cases {
+        let clear_kind = ClearKind::from_rows_cleared(rows_cleared, spin).unwrap();
+
+        assert_eq!(clear_kind, expected_kind);
+        assert_eq!(clear_kind.get_rows_cleared(), rows_cleared);
+        assert_eq!(clear_kind.get_points(), expected_points, "{}", clear_kind);
+        assert_eq!(
+            clear_kind.is_difficult(),
+            expected_is_difficult,
+            "{}",
+            clear_kind
+        );
+    }
+
+    assert_eq!(ClearKind::from_rows_cleared(0, SpinKind::None), None);
+    assert_eq!(get_perfect_clear_points(4, false), 2000);
+    assert_eq!(get_perfect_clear_points(4, true), 3200);
+}
+
+#[test]
+fn tetris_scores_and_starts_a_back_to_back_chain() {
+    let mut game_state = new_game_ready_for_tetris();
+    let level = game_state.get_level();
+    let target = find_target(&game_state, is_in_first_column);
+    let mut inputs = plan_inputs(&game_state, target, false).unwrap();
+    let hard_drop_input = inputs.pop().unwrap();
+
+    for input in inputs {
+        game_state.step(input);
+    }
+
+    let score = game_state.get_score();
+    let rows_dropped = (target.row - game_state.get_active_piece_position().row) as usize;
+    game_state.step(hard_drop_input);
+
+    assert_eq!(game_state.get_rows_cleared(), TETRIS_ROWS);
+    assert_eq!(game_state.get_last_clear_kind(), Some(ClearKind::Tetris));
+    assert_eq!(
+        game_state.get_score() - score,
+        800 * level + 2 * rows_dropped
+    );
+    assert!(game_state.get_is_back_to_back());
+    assert_eq!(game_state.get_combo(), 0);
+    assert!(!game_state.get_last_clear_was_perfect());
+}
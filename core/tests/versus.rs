@@ -0,0 +1,93 @@
+mod common;
+
+use bloxide_core::attack::AttackTable;
+use bloxide_core::game_state::{GameInput, GameState};
+use bloxide_core::grid::GRID_COUNT_ROWS;
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::placement::plan_inputs;
+use bloxide_core::randomizer::RandomizerKind;
+use bloxide_core::versus::{Versus, PLAYER_COUNT};
+use common::{
+    find_target, is_in_first_column, new_game_ready_for_tetris, place_piece, TETRIS_ROWS,
+};
+
+fn count_garbage_rows(game_state: &GameState) -> usize {
+    (0..GRID_COUNT_ROWS)
+        .filter(|&row_id| game_state.get_grid_locked().is_garbage_row(row_id))
+        .count()
+}
+
+#[test]
+fn attacks_cancel_incoming_garbage_before_sending() {
+    // (incoming rows, rows left to land on the stack, rows sent), for a tetris sending 4 rows
+    let cases = [
+        // Partial cancel: the tetris only cancels some of the incoming garbage.
+        (6, 2, 0),
+        // Full cancel: the tetris cancels exactly the incoming garbage.
+        (4, 0, 0),
+        // Overflow: whatever's left after cancelling goes to the opponent.
+        (1, 0, 3),
+        (0, 0, 4),
+    ];
+
+    for (incoming_rows, expected_landed_rows, expected_sent_rows) in cases {
+        let mut game_state = new_game_ready_for_tetris();
+        assert_eq!(count_garbage_rows(&game_state), TETRIS_ROWS);
+
+        game_state.take_outgoing_garbage();
+        game_state.queue_garbage(incoming_rows, 5);
+
+        place_piece(&mut game_state, is_in_first_column);
+
+        assert_eq!(
+            game_state.get_rows_cleared(),
+            TETRIS_ROWS,
+            "{} incoming",
+            incoming_rows
+        );
+        assert_eq!(
+            count_garbage_rows(&game_state),
+            expected_landed_rows,
+            "{} incoming",
+            incoming_rows
+        );
+        assert_eq!(
+            game_state.take_outgoing_garbage(),
+            expected_sent_rows,
+            "{} incoming",
+            incoming_rows
+        );
+    }
+}
+
+#[test]
+fn attacks_left_over_land_on_the_opponents_stack() {
+    let mut versus = Versus::new(
+        RandomizerKind::SevenBag,
+        0,
+        [HandlingConfig::default(); PLAYER_COUNT],
+        AttackTable::default(),
+    );
+    let mut game_state = new_game_ready_for_tetris();
+    let target = find_target(&game_state, is_in_first_column);
+    let inputs = plan_inputs(&game_state, target, false).unwrap();
+
+    game_state.take_outgoing_garbage();
+    versus.players[0] = game_state;
+
+    for input in inputs {
+        versus.step([input, GameInput::default()]);
+    }
+
+    // The garbage lands once the opponent's piece locks, which it does by itself under gravity.
+    for _ in 0..60 * 60 {
+        if count_garbage_rows(&versus.players[1]) > 0 {
+            break;
+        }
+
+        versus.step([GameInput::default(); PLAYER_COUNT]);
+    }
+
+    assert_eq!(count_garbage_rows(&versus.players[1]), TETRIS_ROWS);
+    assert!(!versus.get_is_over());
+}
@@ -1,11 +1,25 @@
-use crate::block::Block;
-use crate::game_state::GameState;
-use crate::grid::{
-    Grid, FIRST_VISIBLE_ROW_ID, GRID_COUNT_COLS, GRID_COUNT_ROWS, VISIBLE_GRID_COUNT_ROWS,
-};
 use crate::high_score_manager::HighScoreManager;
+use crate::high_scores_menu::HighScoresMenu;
 use crate::menu::Menu;
-use crate::piece::{pieces, Piece};
+use crate::replay_menu::ReplayMenu;
+use crate::settings_menu::SettingsMenu;
+use crate::stats_screen::StatsScreen;
+use crate::text_input::TextInput;
+use bloxide_core::block::{Block, BlockColor};
+use bloxide_core::bot::BotStart;
+use bloxide_core::finesse::FinesseResult;
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::GameState;
+use bloxide_core::grid::{
+    Grid, FIRST_VISIBLE_ROW_ID, GRID_COUNT_COLS, GRID_COUNT_ROWS, VISIBLE_GRID_COUNT_ROWS,
+};
+use bloxide_core::leaderboard::{format_date, LeaderboardEntry};
+use bloxide_core::net::{NetHost, NetJoin, NetVersus};
+use bloxide_core::piece::{pieces, Piece};
+use bloxide_core::placement::PiecePosition;
+use bloxide_core::replay::ReplayPlayer;
+use bloxide_core::scoring::ClearKind;
+use bloxide_core::versus::{Versus, PLAYER_COUNT};
 use macroquad::prelude::*;
 use num_format::{Locale, ToFormattedString};
 
@@ -57,6 +71,13 @@ const MENU_OFFSET_Y: f32 = PLAYFIELD_OFFSET_Y + 32.0 + 8.0;
 pub const WINDOW_WIDTH: f32 = PREVIEW_OFFSET_X + PREVIEW_WIDTH + PLAYFIELD_MARGIN;
 pub const WINDOW_HEIGHT: f32 = PLAYFIELD_OFFSET_Y + PLAYFIELD_HEIGHT + PLAYFIELD_MARGIN;
 
+// The stats panel sits to the right of the single player layout, when it's shown.
+const STATS_PANEL_WIDTH: f32 = 180.0;
+pub const STATS_WINDOW_WIDTH: f32 = WINDOW_WIDTH + STATS_PANEL_WIDTH;
+
+// Versus puts each player's layout side by side.
+pub const VERSUS_WINDOW_WIDTH: f32 = WINDOW_WIDTH * PLAYER_COUNT as f32;
+
 pub fn draw_text_centered(
     container_width: f32,
     container_height: Option<f32>,
@@ -76,6 +97,21 @@ pub fn draw_text_centered(
     );
 }
 
+/// Convert a block color from the simulation into a color that can be drawn.
+fn to_color(color: BlockColor) -> Color {
+    color_u8!(color.r, color.g, color.b, 255)
+}
+
+/// Format a time in milliseconds as minutes, seconds, and milliseconds. E.g. 83450 becomes "1:23.450".
+pub fn format_time(time_ms: u64) -> String {
+    format!(
+        "{}:{:02}.{:03}",
+        time_ms / 60_000,
+        (time_ms / 1000) % 60,
+        time_ms % 1000
+    )
+}
+
 fn draw_playfield() {
     draw_rectangle_lines(
         PLAYFIELD_OFFSET_X,
@@ -97,7 +133,7 @@ fn draw_score(score: usize) {
         PLAYFIELD_OFFSET_X + 1.0,
         PLAYFIELD_OFFSET_Y - 9.0,
         40.0,
-        pieces::PIECE_COLOR_L,
+        to_color(pieces::PIECE_COLOR_L),
     );
 
     draw_text_centered(
@@ -111,7 +147,9 @@ fn draw_score(score: usize) {
     );
 }
 
-fn draw_level_and_rows_cleared(level: usize, rows_cleared: usize) {
+/// Draw the level, and a count of rows under it. That's the number of rows cleared, unless the mode has some other
+/// count of rows to show instead.
+fn draw_level_and_rows_cleared(level: usize, rows_label: &str, rows_cleared: usize) {
     draw_text(
         "Level:",
         PREVIEW_OFFSET_X,
@@ -138,7 +176,7 @@ fn draw_level_and_rows_cleared(level: usize, rows_cleared: usize) {
     );
 
     draw_text(
-        "Lines:",
+        rows_label,
         PREVIEW_OFFSET_X,
         PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 78.0,
         32.0,
@@ -154,9 +192,178 @@ fn draw_level_and_rows_cleared(level: usize, rows_cleared: usize) {
     );
 }
 
+/// Draw a bar beside the playfield, as tall as the number of garbage rows waiting to be added to the stack.
+fn draw_pending_garbage(rows: usize) {
+    if rows == 0 {
+        return;
+    }
+
+    let height = rows.min(VISIBLE_GRID_COUNT_ROWS) as f32 * BLOCK_SIZE;
+
+    draw_rectangle(
+        PLAYFIELD_OFFSET_X - (PLAYFIELD_MARGIN / 2.0) - 3.0,
+        OFFSET_INNER_Y + (VISIBLE_GRID_COUNT_ROWS as f32 * BLOCK_SIZE) - height,
+        6.0,
+        height,
+        to_color(pieces::PIECE_COLOR_Z),
+    );
+}
+
+/// Flash the rows that are being cleared, fading out over the line clear delay.
+fn draw_clearing_rows(row_ids: &[usize], progress: f32) {
+    for &row_id in row_ids {
+        if row_id < FIRST_VISIBLE_ROW_ID {
+            continue;
+        }
+
+        draw_rectangle(
+            OFFSET_INNER_X,
+            OFFSET_INNER_Y + ((row_id - FIRST_VISIBLE_ROW_ID) as f32 * BLOCK_SIZE),
+            GRID_COUNT_COLS as f32 * BLOCK_SIZE,
+            BLOCK_SIZE,
+            Color {
+                a: 1.0 - progress,
+                ..WHITE
+            },
+        );
+    }
+}
+
+fn draw_perfect_clears(perfect_clears: usize) {
+    if perfect_clears == 0 {
+        return;
+    }
+
+    draw_line(
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 120.0,
+        PREVIEW_OFFSET_X + PREVIEW_WIDTH,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 120.0,
+        2.0,
+        WHITE,
+    );
+
+    draw_text(
+        "PCs:",
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 146.0,
+        32.0,
+        WHITE,
+    );
+
+    draw_text(
+        &perfect_clears.to_string(),
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 178.0,
+        32.0,
+        WHITE,
+    );
+}
+
+/// Draw the finesse trainer's target for the active piece, as a faint copy of the piece where it should go.
+fn draw_finesse_target(piece: &Piece, target: PiecePosition) {
+    for (row_id, col_id) in target.get_cells(piece) {
+        if row_id < FIRST_VISIBLE_ROW_ID as isize {
+            continue;
+        }
+
+        Block::new(piece.color).draw(DrawBlockArgs {
+            row_id: row_id as usize - FIRST_VISIBLE_ROW_ID,
+            col_id: col_id as usize,
+            offset_x: OFFSET_INNER_X,
+            offset_y: OFFSET_INNER_Y,
+            opacity: 0.25,
+        });
+    }
+}
+
+/// Draw the percentage of pieces placed without a finesse fault, and how the last piece was judged.
+fn draw_finesse(percentage: Option<f32>, last_result: Option<FinesseResult>) {
+    draw_line(
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 120.0,
+        PREVIEW_OFFSET_X + PREVIEW_WIDTH,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 120.0,
+        2.0,
+        WHITE,
+    );
+
+    draw_text(
+        "Finesse:",
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 146.0,
+        32.0,
+        WHITE,
+    );
+
+    draw_text(
+        &percentage.map_or(String::from("-"), |percentage| {
+            format!("{:.1}%", percentage)
+        }),
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 178.0,
+        32.0,
+        WHITE,
+    );
+
+    let Some(result) = last_result else {
+        return;
+    };
+
+    let (text, color) = if !result.is_on_target {
+        (String::from("MISS"), to_color(pieces::PIECE_COLOR_Z))
+    } else if result.get_is_fault() {
+        (
+            format!("FAULT {}/{}", result.inputs, result.min_inputs),
+            to_color(pieces::PIECE_COLOR_Z),
+        )
+    } else {
+        (String::from("OK"), to_color(pieces::PIECE_COLOR_S))
+    };
+
+    draw_text(
+        &text,
+        PREVIEW_OFFSET_X,
+        PREVIEW_OFFSET_Y + PREVIEW_HEIGHT + PLAYFIELD_MARGIN + 206.0,
+        24.0,
+        color,
+    );
+}
+
+/// Draw the finesse faults made with each kind of piece, under the held piece.
+fn draw_finesse_faults(faults_by_piece: [usize; 7]) {
+    for (index, piece) in pieces::ALL.iter().enumerate() {
+        draw_text(
+            &format!("{}: {}", piece.name, faults_by_piece[index]),
+            HOLD_OFFSET_X,
+            HOLD_OFFSET_Y + HOLD_HEIGHT + PLAYFIELD_MARGIN + 10.0 + (24.0 * index as f32),
+            24.0,
+            to_color(piece.color),
+        );
+    }
+}
+
+fn draw_timer(label: &str, time_ms: u64) {
+    draw_text(
+        label,
+        HOLD_OFFSET_X,
+        PLAYFIELD_OFFSET_Y + PLAYFIELD_HEIGHT - 32.0,
+        32.0,
+        WHITE,
+    );
+
+    draw_text(
+        &format_time(time_ms),
+        HOLD_OFFSET_X,
+        PLAYFIELD_OFFSET_Y + PLAYFIELD_HEIGHT,
+        24.0,
+        WHITE,
+    );
+}
+
 fn draw_piece_previews(piece_previews: [Piece; 3]) {
     draw_text(
-        &format!("Next"),
+        "Next",
         PREVIEW_OFFSET_X,
         PREVIEW_OFFSET_Y - 10.0,
         32.0,
@@ -172,8 +379,7 @@ fn draw_piece_previews(piece_previews: [Piece; 3]) {
         WHITE,
     );
 
-    for offset in 0..3 {
-        let piece = piece_previews[offset];
+    for (offset, piece) in piece_previews.iter().enumerate() {
         let piece_w = piece.orientations[0].bounds_x.1 - piece.orientations[0].bounds_x.0;
         let piece_offset_x = ((PREVIEW_PIECE_MAX_BLOCKS_W - piece_w as f32) / 2.0) * BLOCK_SIZE;
 
@@ -188,13 +394,7 @@ fn draw_piece_previews(piece_previews: [Piece; 3]) {
 }
 
 fn draw_held_piece(held_piece: Option<Piece>) {
-    draw_text(
-        &format!("Hold"),
-        HOLD_OFFSET_X,
-        HOLD_OFFSET_Y - 10.0,
-        32.0,
-        WHITE,
-    );
+    draw_text("Hold", HOLD_OFFSET_X, HOLD_OFFSET_Y - 10.0, 32.0, WHITE);
 
     draw_rectangle_lines(
         HOLD_OFFSET_X,
@@ -217,24 +417,234 @@ fn draw_held_piece(held_piece: Option<Piece>) {
     }
 }
 
+fn draw_clear_callouts(
+    last_clear_kind: Option<ClearKind>,
+    is_back_to_back: bool,
+    combo: usize,
+    last_clear_was_perfect: bool,
+) {
+    let mut lines: Vec<(String, Color)> = Vec::new();
+
+    if is_back_to_back {
+        lines.push((String::from("B2B"), to_color(pieces::PIECE_COLOR_O)));
+    }
+
+    if let Some(clear_kind) = last_clear_kind {
+        // The hold column is narrow, so each word of the callout gets its own line.
+        for word in clear_kind.to_string().split(' ') {
+            lines.push((String::from(word), to_color(pieces::PIECE_COLOR_T)));
+        }
+    }
+
+    if combo > 0 {
+        lines.push((format!("{} COMBO", combo), to_color(pieces::PIECE_COLOR_I)));
+    }
+
+    if last_clear_was_perfect {
+        lines.push((String::from("PERFECT"), to_color(pieces::PIECE_COLOR_S)));
+        lines.push((String::from("CLEAR"), to_color(pieces::PIECE_COLOR_S)));
+    }
+
+    for (index, (text, color)) in lines.iter().enumerate() {
+        draw_text(
+            text,
+            HOLD_OFFSET_X,
+            HOLD_OFFSET_Y + HOLD_HEIGHT + PLAYFIELD_MARGIN + 10.0 + (24.0 * index as f32),
+            24.0,
+            *color,
+        );
+    }
+}
+
+fn draw_seed(seed: u64) {
+    let offset_y = PLAYFIELD_OFFSET_Y + PLAYFIELD_HEIGHT - 48.0;
+
+    draw_rectangle(
+        PLAYFIELD_OFFSET_X,
+        offset_y,
+        PLAYFIELD_WIDTH,
+        48.0,
+        color_u8!(80, 80, 80, 255),
+    );
+
+    draw_text_centered(
+        PLAYFIELD_WIDTH,
+        None,
+        &format!("Seed: {}", seed),
+        PLAYFIELD_OFFSET_X,
+        offset_y + 31.0,
+        24.0,
+        WHITE,
+    );
+}
+
+fn draw_player_label(index: usize) {
+    draw_caption(&format!("Player {}", index + 1));
+}
+
+/// Draw a short line of text under the held piece, e.g. to say who's playing.
+pub fn draw_caption(text: &str) {
+    draw_text(
+        text,
+        HOLD_OFFSET_X,
+        PLAYFIELD_OFFSET_Y + PLAYFIELD_HEIGHT,
+        24.0,
+        WHITE,
+    );
+}
+
+/// Move the origin of the versus window right by the given amount, so the single player layout can be drawn
+/// anywhere in it.
+fn set_versus_camera(offset_x: f32) {
+    set_camera(&Camera2D::from_display_rect(Rect::new(
+        -offset_x,
+        0.0,
+        VERSUS_WINDOW_WIDTH,
+        WINDOW_HEIGHT,
+    )));
+}
+
+/// Draw a menu in the middle of the versus window, between the two playfields.
+pub fn draw_versus_menu(menu: &Menu) {
+    set_versus_camera((VERSUS_WINDOW_WIDTH - WINDOW_WIDTH) / 2.0);
+    menu.draw(());
+    set_default_camera();
+}
+
 pub trait Drawable {
     type Args;
 
     fn draw(&self, args: Self::Args);
 }
 
-impl<'a> Drawable for GameState<'a> {
+impl Drawable for GameState {
     type Args = ();
 
     fn draw(&self, _args: ()) {
         draw_playfield();
         draw_score(self.get_score());
-        draw_level_and_rows_cleared(self.get_level(), self.get_rows_cleared());
+        // Cheese races count down the garbage left to dig through, instead of counting up rows cleared. The
+        // finesse trainer never clears rows, so it counts pieces instead.
+        let (rows_label, rows) = match self.get_cheese_remaining() {
+            Some(cheese_remaining) => ("Cheese:", cheese_remaining),
+            None if self.get_mode() == GameMode::Finesse => ("Pieces:", self.get_finesse_pieces()),
+            None => ("Lines:", self.get_rows_cleared()),
+        };
+
+        draw_level_and_rows_cleared(
+            self.get_master_level().unwrap_or(self.get_level()),
+            rows_label,
+            rows,
+        );
+        draw_perfect_clears(self.get_perfect_clears());
         self.get_grid_locked().draw(1.0);
+        draw_clearing_rows(self.get_clearing_rows(), self.get_line_clear_progress());
+        draw_pending_garbage(self.get_pending_garbage());
+
+        if let (Some(target), true) = (self.get_finesse_target(), self.get_is_piece_active()) {
+            draw_finesse_target(&self.get_active_piece(), target);
+        }
+
         self.get_grid_active().draw(1.0);
         self.get_grid_ghost().draw(0.5);
         draw_piece_previews(self.get_piece_previews());
         draw_held_piece(self.get_held_piece());
+        draw_clear_callouts(
+            self.get_last_clear_kind(),
+            self.get_is_back_to_back(),
+            self.get_combo(),
+            self.get_last_clear_was_perfect(),
+        );
+
+        if self.get_mode() == GameMode::Finesse {
+            draw_finesse(
+                self.get_finesse_percentage(),
+                self.get_last_finesse_result(),
+            );
+            draw_finesse_faults(self.get_finesse_faults_by_piece());
+        }
+
+        if let Some(remaining_ms) = self.get_remaining_ms() {
+            draw_timer("Left:", remaining_ms);
+        } else if matches!(self.get_mode(), GameMode::Sprint | GameMode::Cheese(_)) {
+            draw_timer("Time:", self.get_elapsed_ms());
+        }
+
+        if self.get_is_game_over() {
+            draw_seed(self.get_seed());
+        }
+    }
+}
+
+impl Drawable for Versus {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        for (index, game_state) in self.players.iter().enumerate() {
+            set_versus_camera(WINDOW_WIDTH * index as f32);
+            game_state.draw(());
+            draw_player_label(index);
+        }
+
+        set_default_camera();
+    }
+}
+
+impl Drawable for NetVersus {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        self.versus.draw(());
+
+        let status = match (self.get_disconnect_reason(), self.get_latency()) {
+            (Some(reason), _) => String::from(reason.get_label()),
+            (None, Some(latency)) => format!("Ping: {}ms", latency.as_millis()),
+            (None, None) => String::from("Ping: -"),
+        };
+
+        // The connection status goes under the opponent's hold piece.
+        set_versus_camera(WINDOW_WIDTH * (1 - self.get_local_index()) as f32);
+        draw_text(
+            &status,
+            HOLD_OFFSET_X,
+            PLAYFIELD_OFFSET_Y + PLAYFIELD_HEIGHT - 32.0,
+            24.0,
+            WHITE,
+        );
+        set_default_camera();
+    }
+}
+
+impl Drawable for NetHost {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        let port = self
+            .get_local_addr()
+            .map(|addr| addr.port().to_string())
+            .unwrap_or_default();
+
+        draw_menu_box(
+            "HOSTING",
+            &[format!("Port {}", port), String::from("Waiting...")],
+            usize::MAX,
+        );
+    }
+}
+
+impl Drawable for NetJoin {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        draw_menu_box("JOINING", &["Connecting...", "Esc: Cancel"], usize::MAX);
+    }
+}
+
+impl Drawable for BotStart {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        draw_menu_box("BOT", &["Starting...", "Esc: Cancel"], usize::MAX);
     }
 }
 
@@ -246,15 +656,14 @@ impl Drawable for Grid {
             for col_id in 0..GRID_COUNT_COLS {
                 let cell = self.get_cell(row_id, col_id);
 
-                match cell {
-                    Some(block) => block.draw(DrawBlockArgs {
+                if let Some(block) = cell {
+                    block.draw(DrawBlockArgs {
                         row_id: row_id - FIRST_VISIBLE_ROW_ID,
                         col_id,
                         offset_x: OFFSET_INNER_X,
                         offset_y: OFFSET_INNER_Y,
                         opacity,
-                    }),
-                    None => (),
+                    });
                 }
             }
         }
@@ -280,9 +689,9 @@ impl Drawable for Piece {
         let (blocks, _, _) = self.get_blocks(orientation);
         let (min_row, max_row, min_col, max_col) = self.get_trimmed_bounds(orientation);
 
-        for row_id in min_row..max_row {
-            for col_id in min_col..max_col {
-                if let Some(block) = blocks[row_id][col_id] {
+        for (row_id, block_row) in blocks.iter().enumerate().take(max_row).skip(min_row) {
+            for (col_id, block) in block_row.iter().enumerate().take(max_col).skip(min_col) {
+                if let Some(block) = block {
                     block.draw(DrawBlockArgs {
                         row_id: row_id - min_row,
                         col_id: col_id - min_col,
@@ -322,10 +731,8 @@ impl Drawable for Block {
             BLOCK_SIZE,
             BLOCK_SIZE,
             Color {
-                r: self.color.r,
-                g: self.color.g,
-                b: self.color.b,
                 a: opacity,
+                ..to_color(self.color)
             },
         );
 
@@ -345,6 +752,55 @@ impl Drawable for Block {
     }
 }
 
+/// Draw a menu box over the top of the playfield, with a title and one row per label. The active row is
+/// highlighted.
+fn draw_menu_box<S: AsRef<str>>(title: &str, labels: &[S], active_index: usize) {
+    let container_height = 64.0 + (32.0 * labels.len() as f32);
+
+    draw_rectangle(
+        PLAYFIELD_OFFSET_X,
+        PLAYFIELD_OFFSET_Y,
+        PLAYFIELD_WIDTH,
+        container_height,
+        color_u8!(80, 80, 80, 255),
+    );
+
+    draw_rectangle_lines(
+        PLAYFIELD_OFFSET_X,
+        PLAYFIELD_OFFSET_Y,
+        PLAYFIELD_WIDTH,
+        container_height,
+        4.0,
+        to_color(pieces::PIECE_COLOR_L),
+    );
+
+    draw_text_centered(
+        PLAYFIELD_WIDTH,
+        None,
+        title,
+        PLAYFIELD_OFFSET_X,
+        MENU_OFFSET_Y,
+        32.0,
+        WHITE,
+    );
+
+    for (index, label) in labels.iter().enumerate() {
+        draw_text_centered(
+            PLAYFIELD_WIDTH,
+            None,
+            label.as_ref(),
+            PLAYFIELD_OFFSET_X,
+            MENU_OFFSET_Y + (32.0 * (index + 1) as f32),
+            24.0,
+            if active_index == index {
+                to_color(pieces::PIECE_COLOR_L)
+            } else {
+                WHITE
+            },
+        )
+    }
+}
+
 impl<'a> Drawable for Menu<'a> {
     type Args = ();
 
@@ -353,7 +809,73 @@ impl<'a> Drawable for Menu<'a> {
             return;
         }
 
-        let container_height = 64.0 + (32.0 * self.items.len() as f32);
+        let labels: Vec<&str> = self.items.iter().map(|item| item.label).collect();
+        draw_menu_box(self.title, &labels, self.active_index);
+    }
+}
+
+/// Draw a stat's label on the left of the given width, and its value on the right.
+fn draw_stat_row(
+    label: &str,
+    value: &str,
+    offset_x: f32,
+    width: f32,
+    offset_y: f32,
+    text_size: f32,
+) {
+    draw_text(label, offset_x, offset_y, text_size, WHITE);
+    draw_text(
+        value,
+        offset_x + width - (value.len() as f32 * text_size * TEXT_HEIGHT_WIDTH_RATIO),
+        offset_y,
+        text_size,
+        to_color(pieces::PIECE_COLOR_L),
+    );
+}
+
+/// Draw a game's stats in a panel to the right of the single player layout.
+pub fn draw_stats_panel(rows: &[(&str, String)]) {
+    let panel_width = STATS_PANEL_WIDTH - PLAYFIELD_MARGIN;
+
+    draw_text(
+        "Stats",
+        WINDOW_WIDTH,
+        PLAYFIELD_OFFSET_Y - 10.0,
+        32.0,
+        WHITE,
+    );
+
+    draw_rectangle_lines(
+        WINDOW_WIDTH,
+        PLAYFIELD_OFFSET_Y,
+        panel_width,
+        PLAYFIELD_HEIGHT,
+        OUTLINE_WIDTH,
+        WHITE,
+    );
+
+    for (index, (label, value)) in rows.iter().enumerate() {
+        draw_stat_row(
+            label,
+            value,
+            WINDOW_WIDTH + 10.0,
+            panel_width - 20.0,
+            PLAYFIELD_OFFSET_Y + 30.0 + (26.0 * index as f32),
+            24.0,
+        );
+    }
+}
+
+impl Drawable for StatsScreen {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        if !self.is_visible {
+            return;
+        }
+
+        // Room for the title, every stat, and two lines of controls.
+        let container_height = 48.0 + (21.0 * (self.rows.len() + 2) as f32);
 
         draw_rectangle(
             PLAYFIELD_OFFSET_X,
@@ -369,48 +891,196 @@ impl<'a> Drawable for Menu<'a> {
             PLAYFIELD_WIDTH,
             container_height,
             4.0,
-            pieces::PIECE_COLOR_L,
+            to_color(pieces::PIECE_COLOR_L),
         );
 
         draw_text_centered(
             PLAYFIELD_WIDTH,
             None,
-            self.title,
+            "STATS",
             PLAYFIELD_OFFSET_X,
             MENU_OFFSET_Y,
             32.0,
             WHITE,
         );
 
-        for (index, item) in self.items.iter().enumerate() {
+        for (index, (label, value)) in self.rows.iter().enumerate() {
+            draw_stat_row(
+                label,
+                value,
+                PLAYFIELD_OFFSET_X + 12.0,
+                PLAYFIELD_WIDTH - 24.0,
+                MENU_OFFSET_Y + 24.0 + (21.0 * index as f32),
+                20.0,
+            );
+        }
+
+        let controls = match &self.status {
+            Some(status) => vec![status.as_str()],
+            None => vec!["Enter: Export JSON", "Esc: Back"],
+        };
+
+        for (index, line) in controls.iter().enumerate() {
+            draw_text_centered(
+                PLAYFIELD_WIDTH,
+                None,
+                line,
+                PLAYFIELD_OFFSET_X,
+                MENU_OFFSET_Y + 24.0 + (21.0 * (self.rows.len() + index) as f32),
+                20.0,
+                WHITE,
+            );
+        }
+    }
+}
+
+impl Drawable for SettingsMenu {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        if !self.is_visible {
+            return;
+        }
+
+        let labels: Vec<String> = (0..self.get_row_count())
+            .map(|index| self.get_row_label(index))
+            .collect();
+        draw_menu_box("HANDLING", &labels, self.active_index);
+    }
+}
+
+impl Drawable for ReplayMenu {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        if !self.is_visible {
+            return;
+        }
+
+        let labels: Vec<String> = (0..self.get_row_count())
+            .map(|index| self.get_row_label(index))
+            .collect();
+        draw_menu_box("REPLAYS", &labels, self.active_index);
+    }
+}
+
+impl Drawable for ReplayPlayer {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        self.game_state.draw(());
+
+        let status = if let Some(tick) = self.get_divergence_tick() {
+            format!("Diverged: {}", tick)
+        } else if self.get_is_finished() {
+            String::from("Finished")
+        } else {
+            format!("Replay: {}", self.speed.get_label())
+        };
+
+        let mut lines = vec![status];
+
+        if self.get_is_rules_mismatch() {
+            lines.push(String::from("Old rules"));
+        }
+
+        let container_height = 8.0 + (28.0 * lines.len() as f32);
+
+        draw_rectangle(
+            PLAYFIELD_OFFSET_X,
+            PLAYFIELD_OFFSET_Y,
+            PLAYFIELD_WIDTH,
+            container_height,
+            color_u8!(80, 80, 80, 255),
+        );
+
+        for (index, line) in lines.iter().enumerate() {
             draw_text_centered(
                 PLAYFIELD_WIDTH,
                 None,
-                item.label,
+                line,
                 PLAYFIELD_OFFSET_X,
-                MENU_OFFSET_Y + (32.0 * (index + 1) as f32),
+                PLAYFIELD_OFFSET_Y + 28.0 + (28.0 * index as f32),
                 24.0,
-                if self.active_index == index {
-                    pieces::PIECE_COLOR_L
+                if self.get_divergence_tick().is_some() {
+                    to_color(pieces::PIECE_COLOR_Z)
                 } else {
                     WHITE
                 },
-            )
+            );
         }
     }
 }
 
-impl Drawable for HighScoreManager {
+impl<'a> Drawable for TextInput<'a> {
     type Args = ();
 
     fn draw(&self, _args: ()) {
+        if !self.is_visible {
+            return;
+        }
+
+        let container_height = 64.0 + 32.0;
+
+        draw_rectangle(
+            PLAYFIELD_OFFSET_X,
+            PLAYFIELD_OFFSET_Y,
+            PLAYFIELD_WIDTH,
+            container_height,
+            color_u8!(80, 80, 80, 255),
+        );
+
+        draw_rectangle_lines(
+            PLAYFIELD_OFFSET_X,
+            PLAYFIELD_OFFSET_Y,
+            PLAYFIELD_WIDTH,
+            container_height,
+            4.0,
+            to_color(pieces::PIECE_COLOR_L),
+        );
+
         draw_text_centered(
             PLAYFIELD_WIDTH,
             None,
-            &format!(
+            self.title,
+            PLAYFIELD_OFFSET_X,
+            MENU_OFFSET_Y,
+            32.0,
+            WHITE,
+        );
+
+        draw_text_centered(
+            PLAYFIELD_WIDTH,
+            None,
+            &format!("{}_", self.value),
+            PLAYFIELD_OFFSET_X,
+            MENU_OFFSET_Y + 32.0,
+            24.0,
+            to_color(pieces::PIECE_COLOR_L),
+        );
+    }
+}
+
+impl Drawable for HighScoreManager {
+    type Args = GameMode;
+
+    fn draw(&self, mode: GameMode) {
+        let text = match mode {
+            GameMode::Marathon | GameMode::Ultra | GameMode::Master => format!(
                 "High Score: {}",
-                self.get_high_score().to_formatted_string(&Locale::en)
+                self.get_high_score(mode).to_formatted_string(&Locale::en)
             ),
+            GameMode::Sprint | GameMode::Cheese(_) => match self.get_best_time(mode) {
+                Some(best_time) => format!("Best Time: {}", format_time(best_time)),
+                None => String::from("Best Time: -"),
+            },
+            GameMode::Finesse => format!("Best Finesse: {}%", self.get_high_score(mode)),
+        };
+
+        draw_text_centered(
+            PLAYFIELD_WIDTH,
+            None,
+            &text,
             PLAYFIELD_OFFSET_X,
             28.0,
             32.0,
@@ -418,3 +1088,177 @@ impl Drawable for HighScoreManager {
         );
     }
 }
+
+/// Format an entry's result the way its mode is ranked.
+fn format_entry_result(mode: GameMode, entry: &LeaderboardEntry) -> String {
+    match mode {
+        GameMode::Sprint | GameMode::Cheese(_) => format_time(entry.time_ms),
+        GameMode::Finesse => format!("{}%", entry.score),
+        GameMode::Marathon | GameMode::Ultra | GameMode::Master => {
+            entry.score.to_formatted_string(&Locale::en)
+        }
+    }
+}
+
+/// Draw text with its right edge at the given position.
+fn draw_text_right(text: &str, right_x: f32, offset_y: f32, text_size: f32, color: Color) {
+    draw_text(
+        text,
+        right_x - (text.len() as f32 * text_size * TEXT_HEIGHT_WIDTH_RATIO),
+        offset_y,
+        text_size,
+        color,
+    );
+}
+
+impl Drawable for HighScoresMenu {
+    type Args = ();
+
+    fn draw(&self, _args: ()) {
+        if !self.is_visible {
+            return;
+        }
+
+        let mode = self.get_mode();
+        let text_size = 20.0;
+        let rows_offset_y = PLAYFIELD_OFFSET_Y + 80.0;
+        // Columns: rank, name, result, lines, then date. The result and lines are lined up on their right edge.
+        let rank_x = PLAYFIELD_MARGIN;
+        let name_x = rank_x + 32.0;
+        let result_right_x = name_x + 240.0;
+        let lines_right_x = result_right_x + 64.0;
+        let date_x = lines_right_x + 20.0;
+
+        draw_text_centered(
+            WINDOW_WIDTH,
+            None,
+            "HIGH SCORES",
+            0.0,
+            PLAYFIELD_OFFSET_Y,
+            32.0,
+            WHITE,
+        );
+
+        draw_text_centered(
+            WINDOW_WIDTH,
+            None,
+            &format!("< {} >", mode.get_label()),
+            0.0,
+            PLAYFIELD_OFFSET_Y + 32.0,
+            24.0,
+            to_color(pieces::PIECE_COLOR_L),
+        );
+
+        let header_offset_y = rows_offset_y - 24.0;
+        let result_header = if mode.get_is_timed() {
+            "Time"
+        } else if mode == GameMode::Finesse {
+            "Finesse"
+        } else {
+            "Score"
+        };
+
+        draw_text("#", rank_x, header_offset_y, text_size, GRAY);
+        draw_text("Name", name_x, header_offset_y, text_size, GRAY);
+        draw_text_right(
+            result_header,
+            result_right_x,
+            header_offset_y,
+            text_size,
+            GRAY,
+        );
+        draw_text_right("Lines", lines_right_x, header_offset_y, text_size, GRAY);
+        draw_text("Date", date_x, header_offset_y, text_size, GRAY);
+
+        if self.entries.is_empty() {
+            draw_text_centered(
+                WINDOW_WIDTH,
+                None,
+                "No entries yet",
+                0.0,
+                rows_offset_y,
+                text_size,
+                WHITE,
+            );
+        }
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let offset_y = rows_offset_y + (24.0 * index as f32);
+            let color = if index == self.active_index {
+                to_color(pieces::PIECE_COLOR_L)
+            } else {
+                WHITE
+            };
+
+            draw_text(&(index + 1).to_string(), rank_x, offset_y, text_size, color);
+            draw_text(&entry.name, name_x, offset_y, text_size, color);
+            draw_text_right(
+                &format_entry_result(mode, entry),
+                result_right_x,
+                offset_y,
+                text_size,
+                color,
+            );
+            draw_text_right(
+                &entry.lines.to_string(),
+                lines_right_x,
+                offset_y,
+                text_size,
+                color,
+            );
+            draw_text(
+                &entry.date.map_or(String::from("-"), format_date),
+                date_x,
+                offset_y,
+                text_size,
+                color,
+            );
+        }
+
+        // The selected entry's level and seed, which there isn't room for in the table
+        if let Some(entry) = self.entries.get(self.active_index) {
+            let level_label = if mode == GameMode::Master {
+                "Master Level"
+            } else {
+                "Level"
+            };
+
+            let details = match entry.seed {
+                Some(seed) => format!("{}: {}  Seed: {}", level_label, entry.level, seed),
+                None => format!("{}: -  Seed: -", level_label),
+            };
+
+            draw_text_centered(
+                WINDOW_WIDTH,
+                None,
+                &details,
+                0.0,
+                WINDOW_HEIGHT - PLAYFIELD_MARGIN - 28.0,
+                text_size,
+                WHITE,
+            );
+        }
+
+        if let Some(warning) = self.warning {
+            draw_text_centered(
+                WINDOW_WIDTH,
+                None,
+                warning,
+                0.0,
+                WINDOW_HEIGHT - PLAYFIELD_MARGIN - 56.0,
+                text_size,
+                to_color(pieces::PIECE_COLOR_Z),
+            );
+        }
+
+        draw_text_centered(
+            WINDOW_WIDTH,
+            None,
+            "Left/Right: Mode  Esc: Back",
+            0.0,
+            WINDOW_HEIGHT - PLAYFIELD_MARGIN,
+            text_size,
+            GRAY,
+        );
+    }
+}
@@ -1,32 +1,150 @@
-use std::{cell::Cell, fs};
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::GameState;
+use bloxide_core::leaderboard::{Leaderboard, LeaderboardEntry, LeaderboardError};
+use std::{
+    cell::{Cell, RefCell},
+    fs,
+};
+
+// Before the leaderboard, the one high score was kept on its own in this file, from back when Marathon was the
+// only mode. It's carried over the first time the leaderboard is loaded.
+const LEGACY_HIGH_SCORE_PATH: &str = "./.highscore";
+const LEGACY_NAME: &str = "Player";
 
 pub struct HighScoreManager {
-    high_score: Cell<usize>,
+    leaderboard: RefCell<Leaderboard>,
+    // Set when the stored leaderboard couldn't be read, or moved aside once it turned out to be broken. Saving
+    // would overwrite it, so nothing is saved.
+    is_save_disabled: bool,
+    // A problem loading or saving the leaderboard, for the high scores screen to point out
+    warning: Cell<Option<&'static str>>,
 }
 
 impl HighScoreManager {
     pub fn new() -> Self {
-        let read_high_score = fs::read_to_string("./.highscore")
-            .and_then(|data| data.parse::<usize>().or(Ok(0)))
-            .unwrap_or(0);
+        let path = Leaderboard::get_path();
+
+        if !path.exists() {
+            return Self {
+                leaderboard: RefCell::new(load_legacy_leaderboard()),
+                is_save_disabled: false,
+                warning: Cell::new(None),
+            };
+        }
+
+        let (is_save_disabled, warning) = match Leaderboard::load_from(&path) {
+            Ok(leaderboard) => {
+                return Self {
+                    leaderboard: RefCell::new(leaderboard),
+                    is_save_disabled: false,
+                    warning: Cell::new(None),
+                }
+            }
+            // A broken file is kept, but out of the way, so there's a fresh one to save to.
+            Err(error @ LeaderboardError::Invalid(_)) => {
+                eprintln!("Unable to load leaderboard: {}", error);
+
+                match Leaderboard::back_up(&path) {
+                    Ok(backup_path) => {
+                        eprintln!("Moved the old leaderboard to {}", backup_path.display());
+                        (false, "Old leaderboard unreadable, backed up")
+                    }
+                    Err(error) => {
+                        eprintln!("Unable to back up the old leaderboard: {}", error);
+                        (true, "Leaderboard unreadable, not saving")
+                    }
+                }
+            }
+            Err(error) => {
+                eprintln!("Unable to load leaderboard: {}", error);
+                (true, "Leaderboard unreadable, not saving")
+            }
+        };
 
         Self {
-            high_score: Cell::new(read_high_score),
+            leaderboard: RefCell::new(Leaderboard::default()),
+            is_save_disabled,
+            warning: Cell::new(Some(warning)),
         }
     }
 
-    fn set_score(&self, score: usize) {
-        self.high_score.set(score);
-        fs::write("./.highscore", score.to_string()).expect("Unable to write file");
+    pub fn get_warning(&self) -> Option<&'static str> {
+        self.warning.get()
     }
 
-    pub fn add_score(&self, score: usize) {
-        if score > self.high_score.get() {
-            self.set_score(score)
+    /// Whether a finished game's result would make the leaderboard for its mode.
+    pub fn get_is_qualifying(&self, game_state: &GameState) -> bool {
+        LeaderboardEntry::from_game(game_state, "").is_some_and(|entry| {
+            self.leaderboard
+                .borrow()
+                .get_rank(game_state.get_mode(), &entry)
+                .is_some()
+        })
+    }
+
+    /// Record the result of a finished game under the given name, and save the leaderboard if it made it.
+    pub fn add_game_result(&self, game_state: &GameState, name: &str) {
+        let Some(entry) = LeaderboardEntry::from_game(game_state, name) else {
+            return;
+        };
+
+        let mut leaderboard = self.leaderboard.borrow_mut();
+
+        if leaderboard
+            .add_entry(game_state.get_mode(), entry)
+            .is_some()
+            && !self.is_save_disabled
+        {
+            if let Err(error) = leaderboard.save() {
+                eprintln!("Unable to save leaderboard: {}", error);
+                self.warning.set(Some("Leaderboard couldn't be saved"));
+            }
         }
     }
 
-    pub fn get_high_score(&self) -> usize {
-        self.high_score.get()
+    /// Get the entries for a mode, best first.
+    pub fn get_entries(&self, mode: GameMode) -> Vec<LeaderboardEntry> {
+        self.leaderboard.borrow().get_entries(mode).to_vec()
     }
+
+    pub fn get_high_score(&self, mode: GameMode) -> usize {
+        self.leaderboard
+            .borrow()
+            .get_entries(mode)
+            .first()
+            .map_or(0, |entry| entry.score as usize)
+    }
+
+    pub fn get_best_time(&self, mode: GameMode) -> Option<u64> {
+        self.leaderboard
+            .borrow()
+            .get_entries(mode)
+            .first()
+            .map(|entry| entry.time_ms)
+    }
+}
+
+/// Build a leaderboard from the old high score file, with its score as the only Marathon entry.
+fn load_legacy_leaderboard() -> Leaderboard {
+    let mut leaderboard = Leaderboard::default();
+
+    if let Some(legacy_high_score) = fs::read_to_string(LEGACY_HIGH_SCORE_PATH)
+        .ok()
+        .and_then(|data| data.trim().parse::<u64>().ok())
+    {
+        leaderboard.add_entry(
+            GameMode::Marathon,
+            LeaderboardEntry {
+                name: String::from(LEGACY_NAME),
+                score: legacy_high_score,
+                time_ms: 0,
+                lines: 0,
+                level: 0,
+                date: None,
+                seed: None,
+            },
+        );
+    }
+
+    leaderboard
 }
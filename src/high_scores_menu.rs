@@ -0,0 +1,79 @@
+use crate::menu::MenuInput;
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::leaderboard::LeaderboardEntry;
+
+/// A screen for browsing the leaderboard. Left and right pick the mode, and up and down select an entry to show
+/// its details.
+#[derive(Debug)]
+pub struct HighScoresMenu {
+    // The entries for the selected mode, best first
+    pub entries: Vec<LeaderboardEntry>,
+    // A problem with the stored leaderboard, shown above the controls
+    pub warning: Option<&'static str>,
+    pub mode_index: usize,
+    pub active_index: usize,
+    pub is_visible: bool,
+}
+
+impl HighScoresMenu {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            warning: None,
+            mode_index: 0,
+            active_index: 0,
+            is_visible: false,
+        }
+    }
+
+    pub fn get_mode(&self) -> GameMode {
+        GameMode::ALL[self.mode_index]
+    }
+
+    /// Show the menu, starting on the given mode.
+    pub fn show(&mut self, mode: GameMode) {
+        self.mode_index = GameMode::ALL
+            .iter()
+            .position(|&other| other == mode)
+            .unwrap_or(0);
+        self.active_index = 0;
+        self.is_visible = true;
+    }
+
+    /// Update the menu state with the given input. Returns true once the menu has been closed. The entries are
+    /// left to the caller to fill in for the selected mode.
+    pub fn update(&mut self, input: MenuInput) -> bool {
+        if !self.is_visible {
+            return false;
+        }
+
+        if input.back || input.select {
+            self.is_visible = false;
+            return true;
+        }
+
+        if input.left || input.right {
+            let offset = if input.right { 1 } else { -1 };
+
+            self.mode_index = (self.mode_index as isize + offset)
+                .rem_euclid(GameMode::ALL.len() as isize) as usize;
+            self.active_index = 0;
+        }
+
+        if !self.entries.is_empty() {
+            let mut next_index = self.active_index as isize;
+
+            if input.up {
+                next_index -= 1;
+            }
+
+            if input.down {
+                next_index += 1;
+            }
+
+            self.active_index = next_index.rem_euclid(self.entries.len() as isize) as usize;
+        }
+
+        false
+    }
+}
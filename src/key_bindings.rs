@@ -0,0 +1,85 @@
+use bloxide_core::game_state::GameInput;
+use macroquad::prelude::*;
+
+/// The keys one player uses to control their game, when two players share a keyboard.
+#[derive(Copy, Clone, Debug)]
+pub struct KeyBindings {
+    pub shift_left: KeyCode,
+    pub shift_right: KeyCode,
+    pub soft_drop: KeyCode,
+    pub hard_drop: KeyCode,
+    pub rotate_left: KeyCode,
+    pub rotate_right: KeyCode,
+    pub rotate_180: KeyCode,
+    pub hold_piece: KeyCode,
+}
+
+pub const PLAYER_1_KEY_BINDINGS: KeyBindings = KeyBindings {
+    shift_left: KeyCode::A,
+    shift_right: KeyCode::D,
+    soft_drop: KeyCode::S,
+    hard_drop: KeyCode::W,
+    rotate_left: KeyCode::Q,
+    rotate_right: KeyCode::E,
+    rotate_180: KeyCode::R,
+    hold_piece: KeyCode::LeftShift,
+};
+
+pub const PLAYER_2_KEY_BINDINGS: KeyBindings = KeyBindings {
+    shift_left: KeyCode::Left,
+    shift_right: KeyCode::Right,
+    soft_drop: KeyCode::Down,
+    hard_drop: KeyCode::Up,
+    rotate_left: KeyCode::Comma,
+    rotate_right: KeyCode::Period,
+    rotate_180: KeyCode::Slash,
+    hold_piece: KeyCode::RightShift,
+};
+
+// The local player in a networked match has the keyboard to themselves, so they get the single player keys.
+pub const NET_KEY_BINDINGS: KeyBindings = KeyBindings {
+    shift_left: KeyCode::Left,
+    shift_right: KeyCode::Right,
+    soft_drop: KeyCode::Down,
+    hard_drop: KeyCode::Space,
+    rotate_left: KeyCode::Z,
+    rotate_right: KeyCode::X,
+    rotate_180: KeyCode::A,
+    hold_piece: KeyCode::C,
+};
+
+impl KeyBindings {
+    /// Read this frame's keyboard state into the input for the next tick. Pressed inputs that are still waiting
+    /// in the pending input are kept, so they aren't dropped on frames where no tick elapses.
+    pub fn read_input(&self, pending_input: GameInput) -> GameInput {
+        GameInput {
+            soft_drop: is_key_down(self.soft_drop),
+            shift_left: is_key_down(self.shift_left),
+            shift_right: is_key_down(self.shift_right),
+            rotate_right: pending_input.rotate_right || is_key_pressed(self.rotate_right),
+            rotate_left: pending_input.rotate_left || is_key_pressed(self.rotate_left),
+            rotate_180: pending_input.rotate_180 || is_key_pressed(self.rotate_180),
+            hard_drop: pending_input.hard_drop || is_key_pressed(self.hard_drop),
+            hold_piece: pending_input.hold_piece || is_key_pressed(self.hold_piece),
+            toggle_pause: false,
+            rotate_right_held: is_key_down(self.rotate_right),
+            rotate_left_held: is_key_down(self.rotate_left),
+            rotate_180_held: is_key_down(self.rotate_180),
+            hold_piece_held: is_key_down(self.hold_piece),
+        }
+    }
+}
+
+/// Get the part of an input that still applies after a tick has consumed it, i.e. only the held inputs.
+pub fn get_held_input(input: GameInput) -> GameInput {
+    GameInput {
+        soft_drop: input.soft_drop,
+        shift_left: input.shift_left,
+        shift_right: input.shift_right,
+        rotate_right_held: input.rotate_right_held,
+        rotate_left_held: input.rotate_left_held,
+        rotate_180_held: input.rotate_180_held,
+        hold_piece_held: input.hold_piece_held,
+        ..Default::default()
+    }
+}
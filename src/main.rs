@@ -1,17 +1,47 @@
-mod bag_manager;
-mod block;
 mod draw;
-mod game_state;
-mod grid;
 mod high_score_manager;
+mod high_scores_menu;
+mod key_bindings;
 mod menu;
-mod piece;
+mod replay_menu;
+mod settings_menu;
+mod stats_screen;
+mod text_input;
 
-use draw::{Drawable, WINDOW_HEIGHT, WINDOW_WIDTH};
-use game_state::{GameInput, GameState};
+use bloxide_core::ai::AiPlayer;
+use bloxide_core::attack::AttackTable;
+use bloxide_core::bag_manager::get_random_seed;
+use bloxide_core::bot::{Bot, BotPlayer, BotStart};
+use bloxide_core::finesse::FinesseRecord;
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::game_state::{GameInput, GameState, TICKS_PER_SECOND};
+use bloxide_core::handling::HandlingConfig;
+use bloxide_core::net::{join, DisconnectReason, NetHost, NetJoin, NetVersus, DEFAULT_PORT};
+use bloxide_core::randomizer::RandomizerKind;
+use bloxide_core::replay::{PlaybackSpeed, Replay, ReplayPlayer};
+use bloxide_core::versus::{Versus, PLAYER_COUNT};
+use draw::{
+    draw_caption, draw_stats_panel, draw_versus_menu, Drawable, STATS_WINDOW_WIDTH,
+    VERSUS_WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH,
+};
 use high_score_manager::HighScoreManager;
+use high_scores_menu::HighScoresMenu;
+use key_bindings::{
+    get_held_input, NET_KEY_BINDINGS, PLAYER_1_KEY_BINDINGS, PLAYER_2_KEY_BINDINGS,
+};
 use macroquad::{miniquad::window::quit, prelude::*};
 use menu::{Menu, MenuInput, MenuItem};
+use replay_menu::{ReplayMenu, ReplayMenuAction};
+use settings_menu::SettingsMenu;
+use stats_screen::{get_stats_rows, StatsScreen, StatsScreenAction};
+use text_input::{TextInput, TextInputInput, TextInputResult};
+
+// Upper bound on how much simulation time can be made up for in a single frame, so a long stall (e.g.
+// dragging the window) doesn't result in a burst of ticks.
+const MAX_FRAME_TIME: f32 = 0.25;
+
+// How long the AI demo shows the board after topping out, in seconds, before starting another game
+const AI_DEMO_RESTART_DELAY: f64 = 2.0;
 
 fn window_conf() -> Conf {
     Conf {
@@ -28,15 +58,70 @@ fn window_conf() -> Conf {
 enum CurrentScreen {
     Game,
     MainMenu,
+    SeedEntry,
+    RandomizerSelect,
+    ModeSelect,
+    Settings,
+    ReplayMenu,
+    Replay,
+    HighScores,
+    VersusSelect,
+    Versus,
+    NetHost,
+    JoinEntry,
+    Joining,
+    NetVersus,
+    AiDemo,
+    BotEntry,
+    BotStarting,
+    BotGame,
+}
+
+/// Save a game's replay, and add its finesse to the record.
+fn record_game(game_state: &GameState, finesse_record: &mut FinesseRecord) {
+    finesse_record.add_game(game_state);
+
+    if let Err(error) = finesse_record.save() {
+        eprintln!("Unable to save finesse record: {}", error);
+    }
+
+    if let Err(error) = game_state.get_replay().save() {
+        eprintln!("Unable to save replay: {}", error);
+    }
 }
 
 #[macroquad::main(window_conf)]
 async fn main() {
     let high_score_manager = HighScoreManager::new();
+    // Finesse faults for each kind of piece, over every game played
+    let mut finesse_record = FinesseRecord::load();
     let mut current_screen = CurrentScreen::MainMenu;
 
     // Game state
     let mut maybe_game_state: Option<GameState> = None;
+    let mut is_game_recorded = false;
+    let mut maybe_replay_player: Option<ReplayPlayer> = None;
+    let mut maybe_versus: Option<Versus> = None;
+    let mut maybe_net_host: Option<NetHost> = None;
+    let mut maybe_net_join: Option<NetJoin> = None;
+    let mut maybe_net_versus: Option<NetVersus> = None;
+    let mut ai_player = AiPlayer::default();
+    // When the AI's last game ended, so its final board can be shown for a moment before the next one starts
+    let mut maybe_ai_game_over_time: Option<f64> = None;
+    let mut maybe_bot_start: Option<BotStart> = None;
+    let mut maybe_bot_player: Option<BotPlayer> = None;
+    let mut randomizer_kind = RandomizerKind::SevenBag;
+    let mut mode = GameMode::Marathon;
+    let mut handling = HandlingConfig::load();
+
+    // Fixed-step clock. Frame time is accumulated, and the game is stepped once for each full tick.
+    let tick_duration = 1.0 / TICKS_PER_SECOND;
+    let mut tick_accumulator: f32 = 0.0;
+
+    // Inputs that are only pressed for a single frame are buffered until the next tick, so they aren't
+    // dropped on frames where no tick elapses, or repeated on frames where several do.
+    let mut pending_input = GameInput::default();
+    let mut pending_versus_inputs = [GameInput::default(); 2];
 
     let mut menu_main = Menu::new(
         "bloxide",
@@ -45,6 +130,42 @@ async fn main() {
                 label: "New Game",
                 id: "new_game",
             },
+            MenuItem {
+                label: "Mode",
+                id: "mode",
+            },
+            MenuItem {
+                label: "Play Seed",
+                id: "play_seed",
+            },
+            MenuItem {
+                label: "Randomizer",
+                id: "randomizer",
+            },
+            MenuItem {
+                label: "Versus",
+                id: "versus",
+            },
+            MenuItem {
+                label: "AI Demo",
+                id: "ai_demo",
+            },
+            MenuItem {
+                label: "External Bot",
+                id: "bot",
+            },
+            MenuItem {
+                label: "Replays",
+                id: "replays",
+            },
+            MenuItem {
+                label: "High Scores",
+                id: "high_scores",
+            },
+            MenuItem {
+                label: "Settings",
+                id: "settings",
+            },
             MenuItem {
                 label: "Quit",
                 id: "quit",
@@ -54,6 +175,66 @@ async fn main() {
 
     menu_main.is_visible = true;
 
+    let mut menu_mode = Menu::new(
+        "MODE",
+        GameMode::ALL
+            .iter()
+            .map(|mode| MenuItem {
+                label: mode.get_label(),
+                id: mode.get_id(),
+            })
+            .collect(),
+    );
+
+    let mut menu_randomizer = Menu::new(
+        "RANDOMIZER",
+        RandomizerKind::ALL
+            .iter()
+            .map(|kind| MenuItem {
+                label: kind.get_label(),
+                id: kind.get_id(),
+            })
+            .collect(),
+    );
+
+    let mut settings_menu = SettingsMenu::new(handling);
+    let mut replay_menu = ReplayMenu::new();
+    let mut high_scores_menu = HighScoresMenu::new();
+
+    let mut seed_input = TextInput::new("SEED", 20, |c| c.is_ascii_digit());
+    // Asked for when a finished game makes the leaderboard. The last name entered is filled in next time.
+    let mut name_input = TextInput::new("NEW RECORD", 10, |c| {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    });
+    let mut address_input = TextInput::new("JOIN", 21, |c| {
+        c.is_ascii_alphanumeric() || c == '.' || c == ':' || c == '-'
+    });
+    // The command that starts a bot speaking the Tetris Bot Protocol, followed by its arguments
+    let mut bot_command_input =
+        TextInput::new("BOT COMMAND", 21, |c| c.is_ascii_graphic() || c == ' ');
+
+    let mut menu_versus = Menu::new(
+        "VERSUS",
+        vec![
+            MenuItem {
+                label: "Local",
+                id: "local",
+            },
+            MenuItem {
+                label: "Host (LAN)",
+                id: "host",
+            },
+            MenuItem {
+                label: "Join (LAN)",
+                id: "join",
+            },
+            MenuItem {
+                label: "Back",
+                id: "back_to_main_menu",
+            },
+        ],
+    );
+
     let mut menu_game_over = Menu::new(
         "GAME OVER",
         vec![
@@ -61,6 +242,14 @@ async fn main() {
                 label: "New Game",
                 id: "new_game",
             },
+            MenuItem {
+                label: "Retry Seed",
+                id: "retry_seed",
+            },
+            MenuItem {
+                label: "Stats",
+                id: "stats",
+            },
             MenuItem {
                 label: "Main Menu",
                 id: "back_to_main_menu",
@@ -72,6 +261,9 @@ async fn main() {
         ],
     );
 
+    let mut stats_screen = StatsScreen::new();
+    let mut is_stats_panel_visible = false;
+
     let mut menu_paused = Menu::new(
         "PAUSED",
         vec![
@@ -90,60 +282,731 @@ async fn main() {
         ],
     );
 
+    let mut menu_versus_over = Menu::new(
+        "GAME OVER",
+        vec![
+            MenuItem {
+                label: "Rematch",
+                id: "rematch",
+            },
+            MenuItem {
+                label: "Main Menu",
+                id: "back_to_main_menu",
+            },
+            MenuItem {
+                label: "Quit",
+                id: "quit",
+            },
+        ],
+    );
+
+    let mut menu_net_over = Menu::new(
+        "GAME OVER",
+        vec![
+            MenuItem {
+                label: "Main Menu",
+                id: "back_to_main_menu",
+            },
+            MenuItem {
+                label: "Quit",
+                id: "quit",
+            },
+        ],
+    );
+
     loop {
         clear_background(BLACK);
 
         let menu_input = MenuInput {
             up: is_key_pressed(KeyCode::Up),
             down: is_key_pressed(KeyCode::Down),
+            left: is_key_pressed(KeyCode::Left),
+            right: is_key_pressed(KeyCode::Right),
             select: is_key_pressed(KeyCode::Enter),
+            back: is_key_pressed(KeyCode::Escape),
         };
 
-        if current_screen == CurrentScreen::Game && maybe_game_state.is_some() {
-            let game_state = maybe_game_state.as_mut().unwrap();
-            game_state.update(GameInput {
+        if let (CurrentScreen::Game, Some(game_state)) =
+            (&current_screen, maybe_game_state.as_mut())
+        {
+            pending_input = GameInput {
                 soft_drop: is_key_down(KeyCode::Down),
                 shift_left: is_key_down(KeyCode::Left),
                 shift_right: is_key_down(KeyCode::Right),
-                rotate_right: is_key_pressed(KeyCode::Up),
-                hard_drop: is_key_pressed(KeyCode::Space),
-                hold_piece: is_key_pressed(KeyCode::C),
-                toggle_pause: is_key_pressed(KeyCode::Escape),
-            });
+                rotate_right: pending_input.rotate_right
+                    || is_key_pressed(KeyCode::Up)
+                    || is_key_pressed(KeyCode::X),
+                rotate_left: pending_input.rotate_left || is_key_pressed(KeyCode::Z),
+                rotate_180: pending_input.rotate_180 || is_key_pressed(KeyCode::A),
+                hard_drop: pending_input.hard_drop || is_key_pressed(KeyCode::Space),
+                hold_piece: pending_input.hold_piece || is_key_pressed(KeyCode::C),
+                // Escape closes the stats screen (or skips entering a name) once the game is over, rather than
+                // pausing.
+                toggle_pause: pending_input.toggle_pause
+                    || (is_key_pressed(KeyCode::Escape) && !game_state.get_is_game_over()),
+                rotate_right_held: is_key_down(KeyCode::Up) || is_key_down(KeyCode::X),
+                rotate_left_held: is_key_down(KeyCode::Z),
+                rotate_180_held: is_key_down(KeyCode::A),
+                hold_piece_held: is_key_down(KeyCode::C),
+            };
+
+            if is_key_pressed(KeyCode::Tab) && !name_input.is_visible {
+                is_stats_panel_visible = !is_stats_panel_visible;
+                request_new_screen_size(
+                    if is_stats_panel_visible {
+                        STATS_WINDOW_WIDTH
+                    } else {
+                        WINDOW_WIDTH
+                    },
+                    WINDOW_HEIGHT,
+                );
+            }
+
+            tick_accumulator = (tick_accumulator + get_frame_time()).min(MAX_FRAME_TIME);
+
+            while tick_accumulator >= tick_duration {
+                game_state.step(pending_input);
+                tick_accumulator -= tick_duration;
+
+                // Pressed inputs have been consumed, but held inputs still apply to any remaining ticks.
+                pending_input = get_held_input(pending_input);
+            }
 
-            menu_game_over.is_visible = game_state.get_is_game_over();
+            // Every finished game has its result recorded, and is saved as a replay. Results that make the
+            // leaderboard wait for a name first.
+            if game_state.get_is_game_over() && !is_game_recorded {
+                if high_score_manager.get_is_qualifying(game_state) {
+                    name_input.is_visible = true;
+
+                    // Drain any characters typed before the input was shown.
+                    while get_char_pressed().is_some() {}
+                }
+
+                record_game(game_state, &mut finesse_record);
+                is_game_recorded = true;
+            }
+
+            match stats_screen.update(menu_input) {
+                Some(StatsScreenAction::Export) => stats_screen.export(game_state),
+                Some(StatsScreenAction::Back) | None => (),
+            }
+
+            // The game over menu is hidden while a name is entered, so the key that submits it doesn't also
+            // select a menu item.
+            menu_game_over.is_visible =
+                game_state.get_is_game_over() && !stats_screen.is_visible && !name_input.is_visible;
+            menu_game_over.title = if game_state.get_is_goal_reached() {
+                "FINISHED"
+            } else {
+                "GAME OVER"
+            };
             menu_paused.is_visible = game_state.get_is_paused();
 
             match menu_game_over.update(menu_input) {
-                Some("new_game") => *game_state = GameState::new(&high_score_manager),
+                Some("new_game") => {
+                    is_game_recorded = false;
+                    *game_state = GameState::new(mode, randomizer_kind, get_random_seed(), handling)
+                }
+                Some("retry_seed") => {
+                    is_game_recorded = false;
+                    *game_state = GameState::new(
+                        game_state.get_mode(),
+                        game_state.get_randomizer_kind(),
+                        game_state.get_seed(),
+                        handling,
+                    )
+                }
+                Some("stats") => stats_screen.show(game_state),
                 Some("back_to_main_menu") => current_screen = CurrentScreen::MainMenu,
                 Some("quit") => quit(),
                 _ => (),
             }
 
+            // Games left part way through are recorded too, though they can't make the leaderboard.
             match menu_paused.update(menu_input) {
                 Some("resume") => game_state.toggle_pause(),
-                Some("back_to_main_menu") => current_screen = CurrentScreen::MainMenu,
-                Some("quit") => quit(),
+                Some("back_to_main_menu") => {
+                    if !is_game_recorded {
+                        record_game(game_state, &mut finesse_record);
+                        is_game_recorded = true;
+                    }
+
+                    current_screen = CurrentScreen::MainMenu;
+                }
+                Some("quit") => {
+                    if !is_game_recorded {
+                        record_game(game_state, &mut finesse_record);
+                    }
+
+                    quit();
+                }
                 _ => (),
             }
 
+            let text_input = TextInputInput {
+                typed: get_char_pressed(),
+                backspace: is_key_pressed(KeyCode::Backspace),
+                submit: is_key_pressed(KeyCode::Enter),
+                cancel: is_key_pressed(KeyCode::Escape),
+            };
+
+            match name_input.update(text_input) {
+                Some(TextInputResult::Submitted(name)) => {
+                    high_score_manager.add_game_result(game_state, &name);
+                    name_input.is_visible = false;
+                }
+                // Skipping the name leaves the result off the leaderboard.
+                Some(TextInputResult::Cancelled) => name_input.is_visible = false,
+                None => (),
+            }
+
+            // The stats panel is only for the game, so it's put away along with it.
+            if current_screen == CurrentScreen::MainMenu && is_stats_panel_visible {
+                is_stats_panel_visible = false;
+                request_new_screen_size(WINDOW_WIDTH, WINDOW_HEIGHT);
+            }
+
+            if !game_state.get_is_game_over() {
+                stats_screen.is_visible = false;
+            }
+
             game_state.draw(());
+
+            if is_stats_panel_visible {
+                draw_stats_panel(&get_stats_rows(game_state));
+            }
+
             menu_game_over.draw(());
+            stats_screen.draw(());
+            name_input.draw(());
             menu_paused.draw(());
+        } else if let (CurrentScreen::AiDemo, Some(game_state)) =
+            (&current_screen, maybe_game_state.as_mut())
+        {
+            if is_key_pressed(KeyCode::Escape) {
+                current_screen = CurrentScreen::MainMenu;
+            }
+
+            tick_accumulator = (tick_accumulator + get_frame_time()).min(MAX_FRAME_TIME);
 
-            game_state.clean_up();
+            while tick_accumulator >= tick_duration {
+                game_state.step(ai_player.get_input(game_state));
+                tick_accumulator -= tick_duration;
+            }
+
+            // Demo games aren't recorded. Once one ends, another starts with a new seed.
+            if game_state.get_is_game_over() {
+                let game_over_time = *maybe_ai_game_over_time.get_or_insert(get_time());
+
+                if get_time() - game_over_time >= AI_DEMO_RESTART_DELAY {
+                    *game_state =
+                        GameState::new(mode, randomizer_kind, get_random_seed(), handling);
+                    ai_player = AiPlayer::default();
+                    maybe_ai_game_over_time = None;
+                }
+            }
+
+            game_state.draw(());
+            draw_caption("AI Demo");
+        } else if let (CurrentScreen::BotGame, Some(game_state), Some(bot_player)) = (
+            &current_screen,
+            maybe_game_state.as_mut(),
+            maybe_bot_player.as_mut(),
+        ) {
+            let is_bot_stopped = bot_player.get_error().is_some();
+
+            tick_accumulator = (tick_accumulator + get_frame_time()).min(MAX_FRAME_TIME);
+
+            // Bots take as long as they need to think, so the game waits for them rather than letting the piece
+            // fall.
+            while tick_accumulator >= tick_duration {
+                let input = bot_player.get_input(game_state);
+
+                if bot_player.get_is_thinking() {
+                    tick_accumulator = 0.0;
+                    break;
+                }
+
+                game_state.step(input);
+                tick_accumulator -= tick_duration;
+            }
+
+            if let (false, Some(error)) = (is_bot_stopped, bot_player.get_error()) {
+                eprintln!("Bot stopped playing: {}", error);
+            }
+
+            // Bot games aren't recorded. The last board stays up until the bot is closed.
+            game_state.draw(());
+            draw_caption(if bot_player.get_error().is_some() {
+                "Bot stopped"
+            } else {
+                bot_player.get_bot().get_name()
+            });
+
+            // Dropping the bot tells it to quit, without waiting for it to.
+            if is_key_pressed(KeyCode::Escape) {
+                maybe_bot_player = None;
+                current_screen = CurrentScreen::MainMenu;
+            }
+        } else if let (CurrentScreen::BotStarting, Some(bot_start)) =
+            (&current_screen, maybe_bot_start.as_ref())
+        {
+            // Cancelling just stops waiting. The bot is quit once it has finished starting.
+            if is_key_pressed(KeyCode::Escape) {
+                maybe_bot_start = None;
+                current_screen = CurrentScreen::BotEntry;
+            } else {
+                match bot_start.poll() {
+                    Ok(Some(bot)) => {
+                        maybe_bot_player = Some(BotPlayer::new(bot));
+                        maybe_bot_start = None;
+                        maybe_game_state = Some(GameState::new(
+                            mode,
+                            randomizer_kind,
+                            get_random_seed(),
+                            handling,
+                        ));
+                        current_screen = CurrentScreen::BotGame;
+                        tick_accumulator = 0.0;
+                    }
+                    Ok(None) => bot_start.draw(()),
+                    Err(error) => {
+                        eprintln!("Unable to start bot: {}", error);
+                        maybe_bot_start = None;
+                        current_screen = CurrentScreen::BotEntry;
+                    }
+                }
+            }
+        } else if current_screen == CurrentScreen::BotEntry {
+            let text_input = TextInputInput {
+                typed: get_char_pressed(),
+                backspace: is_key_pressed(KeyCode::Backspace),
+                submit: is_key_pressed(KeyCode::Enter),
+                cancel: is_key_pressed(KeyCode::Escape),
+            };
+
+            match bot_command_input.update(text_input) {
+                Some(TextInputResult::Submitted(value)) => {
+                    let mut words = value.split_whitespace().map(String::from);
+
+                    if let Some(command) = words.next() {
+                        maybe_bot_start = Some(Bot::spawn_in_background(command, words.collect()));
+                        current_screen = CurrentScreen::BotStarting;
+                    }
+                }
+                Some(TextInputResult::Cancelled) => current_screen = CurrentScreen::MainMenu,
+                None => (),
+            }
+
+            bot_command_input.draw(());
+        } else if let (CurrentScreen::Replay, Some(replay_player)) =
+            (&current_screen, maybe_replay_player.as_mut())
+        {
+            if is_key_pressed(KeyCode::Escape) {
+                current_screen = CurrentScreen::ReplayMenu;
+            }
+
+            if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::Right) {
+                replay_player.speed = replay_player.speed.cycle(is_key_pressed(KeyCode::Right));
+            }
+
+            if replay_player.speed == PlaybackSpeed::FrameStep {
+                if is_key_pressed(KeyCode::Space) {
+                    replay_player.step();
+                }
+            } else {
+                tick_accumulator = (tick_accumulator + get_frame_time()).min(MAX_FRAME_TIME);
+
+                while tick_accumulator >= tick_duration {
+                    for _ in 0..replay_player.speed.get_ticks_per_tick() {
+                        replay_player.step();
+                    }
+
+                    tick_accumulator -= tick_duration;
+                }
+            }
+
+            replay_player.draw(());
+        } else if let (CurrentScreen::Versus, Some(versus)) =
+            (&current_screen, maybe_versus.as_mut())
+        {
+            if is_key_pressed(KeyCode::Escape) && !versus.get_is_over() {
+                versus.toggle_pause();
+            }
+
+            pending_versus_inputs = [
+                PLAYER_1_KEY_BINDINGS.read_input(pending_versus_inputs[0]),
+                PLAYER_2_KEY_BINDINGS.read_input(pending_versus_inputs[1]),
+            ];
+
+            tick_accumulator = (tick_accumulator + get_frame_time()).min(MAX_FRAME_TIME);
+
+            while tick_accumulator >= tick_duration {
+                versus.step(pending_versus_inputs);
+                tick_accumulator -= tick_duration;
+                pending_versus_inputs = pending_versus_inputs.map(get_held_input);
+            }
+
+            menu_versus_over.is_visible = versus.get_is_over();
+            menu_versus_over.title = match versus.get_winner() {
+                Some(0) => "PLAYER 1 WINS",
+                Some(_) => "PLAYER 2 WINS",
+                None => "DRAW",
+            };
+            menu_paused.is_visible = versus.get_is_paused() && !versus.get_is_over();
+
+            let mut is_leaving = false;
+
+            match menu_versus_over.update(menu_input) {
+                Some("rematch") => {
+                    *versus = Versus::new(
+                        randomizer_kind,
+                        get_random_seed(),
+                        [handling; PLAYER_COUNT],
+                        AttackTable::load(),
+                    );
+                    tick_accumulator = 0.0;
+                    pending_versus_inputs = Default::default();
+                }
+                Some("back_to_main_menu") => is_leaving = true,
+                Some("quit") => quit(),
+                _ => (),
+            }
+
+            match menu_paused.update(menu_input) {
+                Some("resume") => versus.toggle_pause(),
+                Some("back_to_main_menu") => is_leaving = true,
+                Some("quit") => quit(),
+                _ => (),
+            }
+
+            versus.draw(());
+            draw_versus_menu(&menu_versus_over);
+            draw_versus_menu(&menu_paused);
+
+            if is_leaving {
+                current_screen = CurrentScreen::MainMenu;
+                request_new_screen_size(WINDOW_WIDTH, WINDOW_HEIGHT);
+            }
+        } else if let (CurrentScreen::NetVersus, Some(net_versus)) =
+            (&current_screen, maybe_net_versus.as_mut())
+        {
+            pending_input = NET_KEY_BINDINGS.read_input(pending_input);
+            tick_accumulator = (tick_accumulator + get_frame_time()).min(MAX_FRAME_TIME);
+
+            while tick_accumulator >= tick_duration {
+                // While waiting on the opponent's inputs, the local input is held on to until it can be sent.
+                if net_versus.step(pending_input) {
+                    pending_input = get_held_input(pending_input);
+                }
+
+                tick_accumulator -= tick_duration;
+            }
+
+            menu_net_over.is_visible = net_versus.get_is_over();
+            menu_net_over.title = if net_versus.versus.get_is_over() {
+                match net_versus.versus.get_winner() {
+                    Some(winner) if winner == net_versus.get_local_index() => "YOU WIN",
+                    Some(_) => "YOU LOSE",
+                    None => "DRAW",
+                }
+            } else if net_versus.get_disconnect_reason() == Some(DisconnectReason::Left) {
+                "OPPONENT LEFT"
+            } else {
+                "DISCONNECTED"
+            };
+
+            // There's no pausing a networked match, so escape leaves it.
+            let mut is_leaving = is_key_pressed(KeyCode::Escape) && !net_versus.get_is_over();
+
+            match menu_net_over.update(menu_input) {
+                Some("back_to_main_menu") => is_leaving = true,
+                Some("quit") => {
+                    net_versus.leave();
+                    quit();
+                }
+                _ => (),
+            }
+
+            net_versus.draw(());
+            draw_versus_menu(&menu_net_over);
+
+            if is_leaving {
+                net_versus.leave();
+                maybe_net_versus = None;
+                current_screen = CurrentScreen::MainMenu;
+                request_new_screen_size(WINDOW_WIDTH, WINDOW_HEIGHT);
+            }
+        } else if let (CurrentScreen::NetHost, Some(net_host)) =
+            (&current_screen, maybe_net_host.as_mut())
+        {
+            if is_key_pressed(KeyCode::Escape) {
+                maybe_net_host = None;
+                current_screen = CurrentScreen::MainMenu;
+            } else {
+                match net_host.accept() {
+                    Ok(Some(net_versus)) => {
+                        maybe_net_versus = Some(net_versus);
+                        maybe_net_host = None;
+                        current_screen = CurrentScreen::NetVersus;
+                        tick_accumulator = 0.0;
+                        pending_input = GameInput::default();
+                        request_new_screen_size(VERSUS_WINDOW_WIDTH, WINDOW_HEIGHT);
+                    }
+                    Ok(None) => net_host.draw(()),
+                    Err(error) => {
+                        eprintln!("Player failed to join: {}", error);
+                        net_host.draw(());
+                    }
+                }
+            }
+        } else if let (CurrentScreen::Joining, Some(net_join)) =
+            (&current_screen, maybe_net_join.as_ref())
+        {
+            // Cancelling just stops waiting. The connection is dropped once the handshake thread finishes.
+            if is_key_pressed(KeyCode::Escape) {
+                maybe_net_join = None;
+                current_screen = CurrentScreen::JoinEntry;
+            } else {
+                match net_join.poll() {
+                    Ok(Some(net_versus)) => {
+                        maybe_net_versus = Some(net_versus);
+                        maybe_net_join = None;
+                        current_screen = CurrentScreen::NetVersus;
+                        tick_accumulator = 0.0;
+                        pending_input = GameInput::default();
+                        request_new_screen_size(VERSUS_WINDOW_WIDTH, WINDOW_HEIGHT);
+                    }
+                    Ok(None) => net_join.draw(()),
+                    Err(error) => {
+                        eprintln!("Unable to join: {}", error);
+                        maybe_net_join = None;
+                        current_screen = CurrentScreen::JoinEntry;
+                    }
+                }
+            }
+        } else if current_screen == CurrentScreen::JoinEntry {
+            let text_input = TextInputInput {
+                typed: get_char_pressed(),
+                backspace: is_key_pressed(KeyCode::Backspace),
+                submit: is_key_pressed(KeyCode::Enter),
+                cancel: is_key_pressed(KeyCode::Escape),
+            };
+
+            match address_input.update(text_input) {
+                Some(TextInputResult::Submitted(value)) => {
+                    let address = if value.contains(':') {
+                        value
+                    } else {
+                        format!("{}:{}", value, DEFAULT_PORT)
+                    };
+
+                    maybe_net_join = Some(join(address, handling));
+                    current_screen = CurrentScreen::Joining;
+                }
+                Some(TextInputResult::Cancelled) => current_screen = CurrentScreen::MainMenu,
+                None => (),
+            }
+
+            address_input.draw(());
+        } else if current_screen == CurrentScreen::VersusSelect {
+            match menu_versus.update(menu_input) {
+                Some("local") => {
+                    current_screen = CurrentScreen::Versus;
+                    maybe_versus = Some(Versus::new(
+                        randomizer_kind,
+                        get_random_seed(),
+                        [handling; PLAYER_COUNT],
+                        AttackTable::load(),
+                    ));
+                    tick_accumulator = 0.0;
+                    pending_versus_inputs = Default::default();
+                    request_new_screen_size(VERSUS_WINDOW_WIDTH, WINDOW_HEIGHT);
+                }
+                Some("host") => match NetHost::bind(
+                    ("0.0.0.0", DEFAULT_PORT),
+                    get_random_seed(),
+                    randomizer_kind,
+                    handling,
+                    AttackTable::load(),
+                ) {
+                    Ok(net_host) => {
+                        maybe_net_host = Some(net_host);
+                        current_screen = CurrentScreen::NetHost;
+                    }
+                    Err(error) => eprintln!("Unable to host: {}", error),
+                },
+                Some("join") => {
+                    current_screen = CurrentScreen::JoinEntry;
+                    address_input.is_visible = true;
+
+                    // Drain any characters typed before the input was shown.
+                    while get_char_pressed().is_some() {}
+                }
+                Some("back_to_main_menu") => current_screen = CurrentScreen::MainMenu,
+                _ => (),
+            }
+
+            menu_versus.draw(());
+        } else if current_screen == CurrentScreen::ReplayMenu {
+            match replay_menu.update(menu_input) {
+                Some(ReplayMenuAction::Play(path, speed)) => match Replay::load(&path) {
+                    Ok(replay) => {
+                        let mut replay_player = ReplayPlayer::new(replay);
+                        replay_player.speed = speed;
+                        maybe_replay_player = Some(replay_player);
+                        tick_accumulator = 0.0;
+                        current_screen = CurrentScreen::Replay;
+                    }
+                    Err(error) => eprintln!("Unable to load replay: {}", error),
+                },
+                Some(ReplayMenuAction::Back) => current_screen = CurrentScreen::MainMenu,
+                None => (),
+            }
+
+            replay_menu.draw(());
+        } else if current_screen == CurrentScreen::HighScores {
+            if high_scores_menu.update(menu_input) {
+                current_screen = CurrentScreen::MainMenu;
+            }
+
+            high_scores_menu.entries = high_score_manager.get_entries(high_scores_menu.get_mode());
+            high_scores_menu.draw(());
+        } else if current_screen == CurrentScreen::SeedEntry {
+            let text_input = TextInputInput {
+                typed: get_char_pressed(),
+                backspace: is_key_pressed(KeyCode::Backspace),
+                submit: is_key_pressed(KeyCode::Enter),
+                cancel: is_key_pressed(KeyCode::Escape),
+            };
+
+            match seed_input.update(text_input) {
+                Some(TextInputResult::Submitted(value)) => {
+                    // The input only accepts digits, so this can only fail if the seed is too large for a u64.
+                    if let Ok(seed) = value.parse::<u64>() {
+                        current_screen = CurrentScreen::Game;
+                        is_game_recorded = false;
+                        maybe_game_state =
+                            Some(GameState::new(mode, randomizer_kind, seed, handling));
+                        tick_accumulator = 0.0;
+                        pending_input = GameInput::default();
+                    }
+                }
+                Some(TextInputResult::Cancelled) => current_screen = CurrentScreen::MainMenu,
+                None => (),
+            }
+
+            seed_input.draw(());
+        } else if current_screen == CurrentScreen::RandomizerSelect {
+            if let Some(id) = menu_randomizer.update(menu_input) {
+                if let Some(kind) = RandomizerKind::from_id(id) {
+                    randomizer_kind = kind;
+                }
+
+                current_screen = CurrentScreen::MainMenu;
+            }
+
+            menu_randomizer.draw(());
+        } else if current_screen == CurrentScreen::ModeSelect {
+            if let Some(id) = menu_mode.update(menu_input) {
+                if let Some(selected_mode) = GameMode::from_id(id) {
+                    mode = selected_mode;
+                }
+
+                current_screen = CurrentScreen::MainMenu;
+            }
+
+            menu_mode.draw(());
+        } else if current_screen == CurrentScreen::Settings {
+            if settings_menu.update(menu_input) {
+                handling = settings_menu.handling;
+                current_screen = CurrentScreen::MainMenu;
+            }
+
+            settings_menu.draw(());
         } else {
             match menu_main.update(menu_input) {
                 Some("new_game") => {
                     current_screen = CurrentScreen::Game;
-                    maybe_game_state = Some(GameState::new(&high_score_manager));
+                    is_game_recorded = false;
+                    maybe_game_state = Some(GameState::new(
+                        mode,
+                        randomizer_kind,
+                        get_random_seed(),
+                        handling,
+                    ));
+                    tick_accumulator = 0.0;
+                    pending_input = GameInput::default();
+                }
+                Some("play_seed") => {
+                    current_screen = CurrentScreen::SeedEntry;
+                    seed_input.value.clear();
+                    seed_input.is_visible = true;
+
+                    // Drain any characters typed before the input was shown.
+                    while get_char_pressed().is_some() {}
+                }
+                Some("mode") => {
+                    current_screen = CurrentScreen::ModeSelect;
+                    menu_mode.active_index = GameMode::ALL
+                        .iter()
+                        .position(|&other| other == mode)
+                        .unwrap_or(0);
+                    menu_mode.is_visible = true;
+                }
+                Some("versus") => {
+                    current_screen = CurrentScreen::VersusSelect;
+                    menu_versus.active_index = 0;
+                    menu_versus.is_visible = true;
+                }
+                Some("ai_demo") => {
+                    current_screen = CurrentScreen::AiDemo;
+                    maybe_game_state = Some(GameState::new(
+                        mode,
+                        randomizer_kind,
+                        get_random_seed(),
+                        handling,
+                    ));
+                    ai_player = AiPlayer::default();
+                    maybe_ai_game_over_time = None;
+                    tick_accumulator = 0.0;
+                }
+                Some("bot") => {
+                    current_screen = CurrentScreen::BotEntry;
+                    bot_command_input.is_visible = true;
+
+                    // Drain any characters typed before the input was shown.
+                    while get_char_pressed().is_some() {}
+                }
+                Some("replays") => {
+                    current_screen = CurrentScreen::ReplayMenu;
+                    replay_menu.refresh();
+                    replay_menu.is_visible = true;
+                }
+                Some("high_scores") => {
+                    current_screen = CurrentScreen::HighScores;
+                    high_scores_menu.show(mode);
+                    high_scores_menu.warning = high_score_manager.get_warning();
+                }
+                Some("settings") => {
+                    current_screen = CurrentScreen::Settings;
+                    settings_menu.handling = handling;
+                    settings_menu.active_index = 0;
+                    settings_menu.is_visible = true;
+                }
+                Some("randomizer") => {
+                    current_screen = CurrentScreen::RandomizerSelect;
+                    menu_randomizer.active_index = RandomizerKind::ALL
+                        .iter()
+                        .position(|&kind| kind == randomizer_kind)
+                        .unwrap_or(0);
+                    menu_randomizer.is_visible = true;
                 }
                 Some("quit") => quit(),
                 _ => (),
             }
 
-            high_score_manager.draw(());
+            high_score_manager.draw(mode);
             menu_main.draw(());
         }
 
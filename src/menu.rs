@@ -16,7 +16,10 @@ pub struct Menu<'a> {
 pub struct MenuInput {
     pub up: bool,
     pub down: bool,
+    pub left: bool,
+    pub right: bool,
     pub select: bool,
+    pub back: bool,
 }
 
 impl<'a> Menu<'a> {
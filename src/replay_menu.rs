@@ -0,0 +1,112 @@
+use crate::draw::format_time;
+use crate::menu::MenuInput;
+use bloxide_core::game_mode::GameMode;
+use bloxide_core::replay::{PlaybackSpeed, Replay};
+use num_format::{Locale, ToFormattedString};
+use std::path::PathBuf;
+
+// Only the most recent replays are listed, so the menu fits over the playfield.
+const MAX_ENTRIES: usize = 7;
+
+#[derive(Debug)]
+pub struct ReplayMenuEntry {
+    pub path: PathBuf,
+    pub label: String,
+}
+
+pub enum ReplayMenuAction {
+    Play(PathBuf, PlaybackSpeed),
+    Back,
+}
+
+/// A menu listing saved replays. The first row picks the playback speed, with left and right.
+#[derive(Debug)]
+pub struct ReplayMenu {
+    pub entries: Vec<ReplayMenuEntry>,
+    pub speed: PlaybackSpeed,
+    pub active_index: usize,
+    pub is_visible: bool,
+}
+
+impl ReplayMenu {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            speed: PlaybackSpeed::Normal,
+            active_index: 0,
+            is_visible: false,
+        }
+    }
+
+    /// Reload the list of saved replays. Replays that can't be read are left out.
+    pub fn refresh(&mut self) {
+        self.entries = Replay::list_saved()
+            .into_iter()
+            .filter_map(|path| {
+                let replay = Replay::load(&path).ok()?;
+                let result = match replay.mode {
+                    GameMode::Sprint | GameMode::Cheese(_) => format_time(replay.get_duration_ms()),
+                    _ => replay.score.to_formatted_string(&Locale::en),
+                };
+
+                Some(ReplayMenuEntry {
+                    label: format!("{} {}", replay.mode.get_short_label(), result),
+                    path,
+                })
+            })
+            .take(MAX_ENTRIES)
+            .collect();
+
+        self.active_index = self.active_index.min(self.get_row_count() - 1);
+    }
+
+    /// The number of rows in the menu: the speed, then one per replay, then "Back".
+    pub fn get_row_count(&self) -> usize {
+        self.entries.len() + 2
+    }
+
+    pub fn get_row_label(&self, index: usize) -> String {
+        if index == 0 {
+            format!("Speed: {}", self.speed.get_label())
+        } else if let Some(entry) = self.entries.get(index - 1) {
+            entry.label.clone()
+        } else {
+            String::from("Back")
+        }
+    }
+
+    /// Update the menu state with the given input, and possibly return an action.
+    pub fn update(&mut self, input: MenuInput) -> Option<ReplayMenuAction> {
+        if !self.is_visible {
+            return None;
+        }
+
+        if input.back || (input.select && self.active_index == self.get_row_count() - 1) {
+            return Some(ReplayMenuAction::Back);
+        }
+
+        if self.active_index == 0 && (input.left || input.right) {
+            self.speed = self.speed.cycle(input.right);
+        }
+
+        if input.select && self.active_index > 0 {
+            if let Some(entry) = self.entries.get(self.active_index - 1) {
+                return Some(ReplayMenuAction::Play(entry.path.clone(), self.speed));
+            }
+        }
+
+        let mut next_index = self.active_index as isize;
+
+        if input.up {
+            next_index -= 1;
+        }
+
+        if input.down {
+            next_index += 1;
+        }
+
+        self.active_index = next_index.rem_euclid(self.get_row_count() as isize) as usize;
+
+        None
+    }
+}
@@ -0,0 +1,86 @@
+use crate::menu::MenuInput;
+use bloxide_core::handling::{HandlingConfig, HandlingSetting};
+
+/// A menu for editing handling settings. Up and down select a setting, and left and right change its value.
+#[derive(Debug)]
+pub struct SettingsMenu {
+    pub handling: HandlingConfig,
+    pub active_index: usize,
+    pub is_visible: bool,
+}
+
+impl SettingsMenu {
+    pub fn new(handling: HandlingConfig) -> Self {
+        Self {
+            handling,
+            active_index: 0,
+            is_visible: false,
+        }
+    }
+
+    /// The number of rows in the menu: one per setting, then "Reset", then "Back".
+    pub fn get_row_count(&self) -> usize {
+        HandlingSetting::ALL.len() + 2
+    }
+
+    /// Get the label for the given row, including the current value for settings.
+    pub fn get_row_label(&self, index: usize) -> String {
+        match HandlingSetting::ALL.get(index) {
+            Some(&setting) => format!(
+                "{}: {}",
+                setting.get_label(),
+                self.handling.get_display_value(setting)
+            ),
+            None if index == HandlingSetting::ALL.len() => String::from("Reset"),
+            None => String::from("Back"),
+        }
+    }
+
+    /// Update the menu state with the given input. Returns true once the menu has been closed, at which point
+    /// the settings have been saved.
+    pub fn update(&mut self, input: MenuInput) -> bool {
+        if !self.is_visible {
+            return false;
+        }
+
+        let is_back_selected = self.active_index == self.get_row_count() - 1;
+        let is_reset_selected = self.active_index == self.get_row_count() - 2;
+
+        if input.back || (input.select && is_back_selected) {
+            if let Err(error) = self.handling.save() {
+                eprintln!("Unable to save settings: {}", error);
+            }
+
+            self.is_visible = false;
+            return true;
+        }
+
+        if input.select && is_reset_selected {
+            self.handling = HandlingConfig::default();
+        }
+
+        if let Some(&setting) = HandlingSetting::ALL.get(self.active_index) {
+            if input.left {
+                self.handling.adjust(setting, false);
+            }
+
+            if input.right {
+                self.handling.adjust(setting, true);
+            }
+        }
+
+        let mut next_index = self.active_index as isize;
+
+        if input.up {
+            next_index -= 1;
+        }
+
+        if input.down {
+            next_index += 1;
+        }
+
+        self.active_index = next_index.rem_euclid(self.get_row_count() as isize) as usize;
+
+        false
+    }
+}
@@ -0,0 +1,87 @@
+use crate::draw::format_time;
+use crate::menu::MenuInput;
+use bloxide_core::game_state::GameState;
+use bloxide_core::stats::save_json;
+
+pub enum StatsScreenAction {
+    Export,
+    Back,
+}
+
+/// Get the stats of a game, as (label, value) rows.
+pub fn get_stats_rows(game_state: &GameState) -> Vec<(&'static str, String)> {
+    let stats = game_state.get_stats();
+    let elapsed_ms = game_state.get_elapsed_ms();
+
+    vec![
+        ("Time", format_time(elapsed_ms)),
+        ("Pieces", stats.pieces_placed.to_string()),
+        ("PPS", format!("{:.2}", stats.get_pps(elapsed_ms))),
+        ("Keys", stats.keys_pressed.to_string()),
+        ("KPP", format!("{:.2}", stats.get_kpp())),
+        ("Singles", stats.singles.to_string()),
+        ("Doubles", stats.doubles.to_string()),
+        ("Triples", stats.triples.to_string()),
+        ("Tetrises", stats.tetrises.to_string()),
+        ("T-Spins", stats.t_spins.to_string()),
+        ("PCs", stats.perfect_clears.to_string()),
+        ("Max Combo", stats.max_combo.to_string()),
+        ("Attack", stats.attack_sent.to_string()),
+        ("APM", format!("{:.1}", stats.get_apm(elapsed_ms))),
+    ]
+}
+
+/// A detailed breakdown of a finished game's stats. Select exports them to JSON, and back closes the screen.
+#[derive(Debug)]
+pub struct StatsScreen {
+    pub rows: Vec<(&'static str, String)>,
+    // The result of the last export, shown in place of the controls
+    pub status: Option<String>,
+    pub is_visible: bool,
+}
+
+impl StatsScreen {
+    pub fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            status: None,
+            is_visible: false,
+        }
+    }
+
+    /// Show the stats of the given game.
+    pub fn show(&mut self, game_state: &GameState) {
+        self.rows = get_stats_rows(game_state);
+        self.status = None;
+        self.is_visible = true;
+    }
+
+    /// Update the screen with the given input, and possibly return an action.
+    pub fn update(&mut self, input: MenuInput) -> Option<StatsScreenAction> {
+        if !self.is_visible {
+            return None;
+        }
+
+        if input.back {
+            self.is_visible = false;
+            return Some(StatsScreenAction::Back);
+        }
+
+        if input.select {
+            return Some(StatsScreenAction::Export);
+        }
+
+        None
+    }
+
+    /// Export the given game's stats, and show where they were saved.
+    pub fn export(&mut self, game_state: &GameState) {
+        self.status = Some(match save_json(game_state) {
+            Ok(_) => String::from("Exported to stats/"),
+            Err(error) => {
+                eprintln!("Unable to export stats: {}", error);
+                String::from("Export failed")
+            }
+        });
+    }
+}
@@ -0,0 +1,63 @@
+#[derive(Debug)]
+pub struct TextInput<'a> {
+    pub title: &'a str,
+    pub value: String,
+    pub max_length: usize,
+    pub is_visible: bool,
+    // Characters that aren't accepted by this filter are ignored
+    pub filter: fn(char) -> bool,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TextInputInput {
+    pub typed: Option<char>,
+    pub backspace: bool,
+    pub submit: bool,
+    pub cancel: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextInputResult {
+    Submitted(String),
+    Cancelled,
+}
+
+impl<'a> TextInput<'a> {
+    pub fn new(title: &'a str, max_length: usize, filter: fn(char) -> bool) -> Self {
+        Self {
+            title,
+            value: String::new(),
+            max_length,
+            is_visible: false,
+            filter,
+        }
+    }
+
+    /// Update the input with the given key presses, and possibly return a result if the input was submitted or
+    /// cancelled. Empty values can't be submitted.
+    pub fn update(&mut self, input: TextInputInput) -> Option<TextInputResult> {
+        if !self.is_visible {
+            return None;
+        }
+
+        if input.cancel {
+            return Some(TextInputResult::Cancelled);
+        }
+
+        if input.submit && !self.value.is_empty() {
+            return Some(TextInputResult::Submitted(self.value.clone()));
+        }
+
+        if input.backspace {
+            self.value.pop();
+        }
+
+        if let Some(typed) = input.typed {
+            if (self.filter)(typed) && self.value.chars().count() < self.max_length {
+                self.value.push(typed);
+            }
+        }
+
+        None
+    }
+}